@@ -0,0 +1,316 @@
+use std::fmt;
+
+use crate::algorithms::hasher::Hasher;
+use crate::algorithms::hmac_algo::Hmac;
+use crate::elements::algorithm::Algorithm;
+use crate::utils::error::Error;
+
+// Limits of SP 800-90A table 2, the same for both mechanisms
+const MAX_REQUEST_LEN: usize = 1 << 16;
+const RESEED_INTERVAL: u64 = 1 << 48;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Mechanism {
+    // SP 800-90A section 10.1.2
+    HmacDrbg,
+    // SP 800-90A section 10.1.1
+    HashDrbg,
+}
+
+#[derive(Clone)]
+enum State {
+    Hmac { key: Vec<u8>, value: Vec<u8> },
+    Hash { value: Vec<u8>, constant: Vec<u8> },
+}
+
+// Deterministic random bit generator of SP 800-90A. The same entropy input, nonce and personalization
+// string give the same output, which makes it suitable for reproducible test randomness and for
+// deriving nonces from a secret key and a message. Seeded from the operating system it is a regular
+// generator, with prediction resistance it reseeds before every request so a leaked state reveals
+// none of the later outputs.
+//
+// The approved hash functions are the SHA-2 ones
+#[derive(Clone)]
+pub struct Drbg {
+    mechanism: Mechanism,
+    algorithm: Algorithm,
+    state: State,
+    reseed_counter: u64,
+    prediction_resistance: bool,
+}
+
+impl Mechanism {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mechanism::HmacDrbg => "HMAC_DRBG",
+            Mechanism::HashDrbg => "Hash_DRBG",
+        }
+    }
+}
+
+impl fmt::Display for Mechanism {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Drbg {
+    // Instantiate: the entropy input holds at least the security strength, the nonce at least half of it.
+    // The personalization string separates instances seeded alike, it may be empty
+    pub fn new(
+        mechanism: Mechanism,
+        algorithm: Algorithm,
+        entropy_input: &[u8],
+        nonce: &[u8],
+        personalization: &[u8],
+    ) -> Result<Drbg, Error> {
+        let strength = security_strength(algorithm)?;
+        check_entropy(algorithm, entropy_input)?;
+        if nonce.len() < strength / 2 {
+            return Err(Error::InvalidParameter(format!(
+                "a {} nonce has at least {} bytes",
+                mechanism,
+                strength / 2
+            )));
+        }
+
+        let seed_material = [entropy_input, nonce, personalization].concat();
+        let state = match mechanism {
+            Mechanism::HmacDrbg => {
+                let mut key = vec![0x00; algorithm.output_len()];
+                let mut value = vec![0x01; algorithm.output_len()];
+                hmac_update(algorithm, &mut key, &mut value, &seed_material);
+                State::Hmac { key, value }
+            }
+            Mechanism::HashDrbg => {
+                let value = hash_df(algorithm, &seed_material, seed_len(algorithm));
+                let constant = hash_df(algorithm, &[&[0x00][..], &value].concat(), seed_len(algorithm));
+                State::Hash { value, constant }
+            }
+        };
+        Ok(Drbg {
+            mechanism,
+            algorithm,
+            state,
+            reseed_counter: 1,
+            prediction_resistance: false,
+        })
+    }
+
+    // Entropy input and nonce from the operating system generator. With prediction resistance every
+    // `generate` first reseeds from it
+    pub fn from_os(
+        mechanism: Mechanism,
+        algorithm: Algorithm,
+        personalization: &[u8],
+        prediction_resistance: bool,
+    ) -> Result<Drbg, Error> {
+        let strength = security_strength(algorithm)?;
+        let mut entropy = vec![0u8; strength + strength / 2];
+        getrandom::getrandom(&mut entropy)?;
+        let (entropy_input, nonce) = entropy.split_at(strength);
+        let mut drbg = Drbg::new(mechanism, algorithm, entropy_input, nonce, personalization)?;
+        drbg.prediction_resistance = prediction_resistance;
+        Ok(drbg)
+    }
+
+    pub fn mechanism(&self) -> Mechanism {
+        self.mechanism
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    // Security strength in bytes
+    pub fn security_strength(&self) -> usize {
+        security_strength(self.algorithm).expect("checked when instantiated")
+    }
+
+    pub fn prediction_resistance(&self) -> bool {
+        self.prediction_resistance
+    }
+
+    // Requests since the last reseed, plus one
+    pub fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+
+    pub fn reseed(&mut self, entropy_input: &[u8], additional_input: &[u8]) -> Result<(), Error> {
+        check_entropy(self.algorithm, entropy_input)?;
+        let algorithm = self.algorithm;
+        match &mut self.state {
+            State::Hmac { key, value } => {
+                hmac_update(algorithm, key, value, &[entropy_input, additional_input].concat());
+            }
+            State::Hash { value, constant } => {
+                let seed_material = [&[0x01][..], value, entropy_input, additional_input].concat();
+                *value = hash_df(algorithm, &seed_material, seed_len(algorithm));
+                *constant = hash_df(algorithm, &[&[0x00][..], value].concat(), seed_len(algorithm));
+            }
+        }
+        self.reseed_counter = 1;
+        Ok(())
+    }
+
+    // Fills `output`, at most 64 KiB per request. An instance with prediction resistance reseeds
+    // from the operating system first
+    pub fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), Error> {
+        if self.prediction_resistance {
+            let mut entropy_input = vec![0u8; self.security_strength()];
+            getrandom::getrandom(&mut entropy_input)?;
+            return self.generate_with_prediction_resistance(&entropy_input, output, additional_input);
+        }
+        self.generate_bits(output, additional_input)
+    }
+
+    // Generate with prediction resistance and entropy from the caller: reseeds with the entropy and the
+    // additional input, then generates without additional input
+    pub fn generate_with_prediction_resistance(
+        &mut self,
+        entropy_input: &[u8],
+        output: &mut [u8],
+        additional_input: &[u8],
+    ) -> Result<(), Error> {
+        self.reseed(entropy_input, additional_input)?;
+        self.generate_bits(output, &[])
+    }
+
+    pub fn generate_vec(&mut self, len: usize, additional_input: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut output = vec![0u8; len];
+        self.generate(&mut output, additional_input)?;
+        Ok(output)
+    }
+
+    fn generate_bits(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), Error> {
+        if output.len() > MAX_REQUEST_LEN {
+            return Err(Error::InvalidParameter(format!(
+                "at most {} bytes per request",
+                MAX_REQUEST_LEN
+            )));
+        }
+        if self.reseed_counter > RESEED_INTERVAL {
+            return Err(Error::ReseedRequired);
+        }
+
+        let algorithm = self.algorithm;
+        match &mut self.state {
+            State::Hmac { key, value } => {
+                if !additional_input.is_empty() {
+                    hmac_update(algorithm, key, value, additional_input);
+                }
+                for block in output.chunks_mut(algorithm.output_len()) {
+                    *value = hmac(algorithm, key, &[value]);
+                    block.copy_from_slice(&value[..block.len()]);
+                }
+                hmac_update(algorithm, key, value, additional_input);
+            }
+            State::Hash { value, constant } => {
+                if !additional_input.is_empty() {
+                    let w = hash(algorithm, &[&[0x02], value, additional_input]);
+                    add_assign(value, &w);
+                }
+                // Hashgen: hashes of V, V + 1, V + 2...
+                let mut data = value.clone();
+                for block in output.chunks_mut(algorithm.output_len()) {
+                    let digest = hash(algorithm, &[&data]);
+                    block.copy_from_slice(&digest[..block.len()]);
+                    add_assign(&mut data, &[0x01]);
+                }
+                let h = hash(algorithm, &[&[0x03], value]);
+                add_assign(value, &h);
+                add_assign(value, constant);
+                add_assign(value, &self.reseed_counter.to_be_bytes());
+            }
+        }
+        self.reseed_counter += 1;
+        Ok(())
+    }
+}
+
+// The state is secret, it is not printed
+impl fmt::Debug for Drbg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Drbg")
+            .field("mechanism", &self.mechanism)
+            .field("algorithm", &self.algorithm)
+            .field("reseed_counter", &self.reseed_counter)
+            .field("prediction_resistance", &self.prediction_resistance)
+            .finish_non_exhaustive()
+    }
+}
+
+// SP 800-57 strengths in bytes of the hash functions approved by SP 800-90A
+fn security_strength(algorithm: Algorithm) -> Result<usize, Error> {
+    match algorithm {
+        Algorithm::Sha224 => Ok(24),
+        Algorithm::Sha256 | Algorithm::Sha384 | Algorithm::Sha512 | Algorithm::Sha512_256 => Ok(32),
+        _ => Err(Error::UnsupportedAlgorithm(algorithm.to_string())),
+    }
+}
+
+// seedlen of Hash_DRBG: 440 bits up to 256-bit outputs, 888 bits above
+fn seed_len(algorithm: Algorithm) -> usize {
+    match algorithm.output_len() > 32 {
+        true => 111,
+        false => 55,
+    }
+}
+
+fn check_entropy(algorithm: Algorithm, entropy_input: &[u8]) -> Result<(), Error> {
+    let strength = security_strength(algorithm)?;
+    match entropy_input.len() >= strength {
+        true => Ok(()),
+        false => Err(Error::InvalidParameter(format!(
+            "the entropy input has at least {} bytes with {}",
+            strength, algorithm
+        ))),
+    }
+}
+
+fn hmac(algorithm: Algorithm, key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+    let mut mac = Hmac::new(algorithm, key);
+    parts.iter().for_each(|part| mac.update(part));
+    mac.finalize()
+}
+
+fn hash(algorithm: Algorithm, parts: &[&[u8]]) -> Vec<u8> {
+    let mut hasher = Hasher::new(algorithm);
+    parts.iter().for_each(|part| hasher.update(part));
+    hasher.finalize().into_bytes()
+}
+
+// HMAC_DRBG_Update: the second round only runs with provided data
+fn hmac_update(algorithm: Algorithm, key: &mut Vec<u8>, value: &mut Vec<u8>, provided_data: &[u8]) {
+    *key = hmac(algorithm, key, &[value, &[0x00], provided_data]);
+    *value = hmac(algorithm, key, &[value]);
+    if !provided_data.is_empty() {
+        *key = hmac(algorithm, key, &[value, &[0x01], provided_data]);
+        *value = hmac(algorithm, key, &[value]);
+    }
+}
+
+// Hash_df: hashes of counter || output length in bits || input, truncated to `len` bytes
+fn hash_df(algorithm: Algorithm, input: &[u8], len: usize) -> Vec<u8> {
+    let bits = (len as u32 * 8).to_be_bytes();
+    let mut output = Vec::with_capacity(len + algorithm.output_len());
+    for counter in 1u8.. {
+        if output.len() >= len {
+            break;
+        }
+        output.extend_from_slice(&hash(algorithm, &[&[counter], &bits, input]));
+    }
+    output.truncate(len);
+    output
+}
+
+// value = value + addend mod 2^(8 * value length), both big-endian
+fn add_assign(value: &mut [u8], addend: &[u8]) {
+    let mut carry = 0u16;
+    let mut addend = addend.iter().rev();
+    for byte in value.iter_mut().rev() {
+        let sum = u16::from(*byte) + u16::from(*addend.next().unwrap_or(&0)) + carry;
+        *byte = sum as u8;
+        carry = sum >> 8;
+    }
+}
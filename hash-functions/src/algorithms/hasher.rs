@@ -0,0 +1,106 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use sha2::digest::DynDigest;
+use sha2::{Sha224, Sha256, Sha384, Sha512, Sha512_256};
+use sha3::{Keccak224, Keccak256, Keccak384, Keccak512, Sha3_224, Sha3_256, Sha3_384, Sha3_512};
+
+use crate::elements::{algorithm::Algorithm, digest::Digest};
+use crate::utils::error::Error;
+
+// Size of the chunks read from a reader. Big enough to keep the syscalls low
+// and small enough to hash huge files without loading them in memory
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+// Incremental hasher where the algorithm is chosen at runtime.
+// `DynDigest` is the object safe version of the `Digest` trait of the RustCrypto crates
+pub struct Hasher {
+    algorithm: Algorithm,
+    inner: Box<dyn DynDigest>,
+}
+
+impl Hasher {
+    pub fn new(algorithm: Algorithm) -> Hasher {
+        let inner: Box<dyn DynDigest> = match algorithm {
+            Algorithm::Sha224 => Box::new(Sha224::default()),
+            Algorithm::Sha256 => Box::new(Sha256::default()),
+            Algorithm::Sha384 => Box::new(Sha384::default()),
+            Algorithm::Sha512 => Box::new(Sha512::default()),
+            Algorithm::Sha512_256 => Box::new(Sha512_256::default()),
+            Algorithm::Sha3_224 => Box::new(Sha3_224::default()),
+            Algorithm::Sha3_256 => Box::new(Sha3_256::default()),
+            Algorithm::Sha3_384 => Box::new(Sha3_384::default()),
+            Algorithm::Sha3_512 => Box::new(Sha3_512::default()),
+            Algorithm::Keccak224 => Box::new(Keccak224::default()),
+            Algorithm::Keccak256 => Box::new(Keccak256::default()),
+            Algorithm::Keccak384 => Box::new(Keccak384::default()),
+            Algorithm::Keccak512 => Box::new(Keccak512::default()),
+        };
+        Hasher { algorithm, inner }
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn finalize(self) -> Digest {
+        let bytes = self.inner.finalize().into_vec();
+        // The length always matches because the hasher was created from the same algorithm
+        Digest::new(self.algorithm, bytes).expect("hasher output length")
+    }
+}
+
+impl Clone for Hasher {
+    fn clone(&self) -> Self {
+        Hasher {
+            algorithm: self.algorithm,
+            inner: self.inner.box_clone(),
+        }
+    }
+}
+
+// Allows to use `io::copy` to feed the hasher
+impl Write for Hasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// One-shot digest of a byte slice
+pub fn digest(algorithm: Algorithm, data: &[u8]) -> Digest {
+    let mut hasher = Hasher::new(algorithm);
+    hasher.update(data);
+    hasher.finalize()
+}
+
+// Digest of everything the reader returns. The input is streamed in fixed size chunks
+pub fn digest_reader<R: Read>(algorithm: Algorithm, mut reader: R) -> Result<Digest, Error> {
+    let mut hasher = Hasher::new(algorithm);
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(Error::Io(e)),
+        };
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize())
+}
+
+// Digest of the file content, read in chunks so big files do not end up in memory
+pub fn digest_file<P: AsRef<Path>>(algorithm: Algorithm, path: P) -> Result<Digest, Error> {
+    let file = File::open(path)?;
+    digest_reader(algorithm, file)
+}
@@ -0,0 +1,96 @@
+use crate::elements::algorithm::Algorithm;
+use crate::utils::error::Error;
+use super::hmac_algo::Hmac;
+
+// The expand step counts the blocks with a single byte, so it can produce 255 blocks at most
+const MAX_BLOCKS: usize = 255;
+
+// HMAC-based key derivation function (RFC 5869).
+// `extract` concentrates the entropy of the input key material in a pseudorandom key (PRK)
+// and `expand` stretches the PRK into as many sub-keys as needed, one per `info` label
+#[derive(Clone)]
+pub struct Hkdf {
+    algorithm: Algorithm,
+    prk: Vec<u8>,
+}
+
+impl Hkdf {
+    // PRK = HMAC-Hash(salt, IKM). Without salt, RFC 5869 uses a string of zeros of the hash length
+    pub fn extract(algorithm: Algorithm, salt: Option<&[u8]>, ikm: &[u8]) -> Hkdf {
+        let zeros = vec![0u8; algorithm.output_len()];
+        let salt = salt.unwrap_or(&zeros);
+
+        let mut mac = Hmac::new(algorithm, salt);
+        mac.update(ikm);
+        Hkdf {
+            algorithm,
+            prk: mac.finalize(),
+        }
+    }
+
+    // Skips the extract step when the input is already a uniformly random key
+    pub fn from_prk(algorithm: Algorithm, prk: &[u8]) -> Result<Hkdf, Error> {
+        if prk.len() < algorithm.output_len() {
+            return Err(Error::InvalidPrkLength {
+                expected: algorithm.output_len(),
+                actual: prk.len(),
+            });
+        }
+        Ok(Hkdf {
+            algorithm,
+            prk: prk.to_vec(),
+        })
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn prk(&self) -> &[u8] {
+        &self.prk
+    }
+
+    // Fills `okm` with T(1) | T(2) | ... where T(i) = HMAC-Hash(PRK, T(i - 1) | info | i).
+    // Different `info` values give independent keys from the same PRK
+    pub fn expand(&self, info: &[u8], okm: &mut [u8]) -> Result<(), Error> {
+        let max = MAX_BLOCKS * self.algorithm.output_len();
+        if okm.len() > max {
+            return Err(Error::InvalidOutputLength { max, actual: okm.len() });
+        }
+
+        let mut previous: Vec<u8> = Vec::new();
+        for (counter, chunk) in okm.chunks_mut(self.algorithm.output_len()).enumerate() {
+            let mut mac = Hmac::new(self.algorithm, &self.prk);
+            mac.update(&previous);
+            mac.update(info);
+            mac.update(&[counter as u8 + 1]);
+            previous = mac.finalize();
+            chunk.copy_from_slice(&previous[..chunk.len()]);
+        }
+        Ok(())
+    }
+
+    pub fn expand_to_vec(&self, info: &[u8], length: usize) -> Result<Vec<u8>, Error> {
+        let mut okm = vec![0u8; length];
+        self.expand(info, &mut okm)?;
+        Ok(okm)
+    }
+
+    // Fixed size keys, e.g. `let secret: [u8; 32] = hkdf.expand_array(b"signing key")?`
+    pub fn expand_array<const N: usize>(&self, info: &[u8]) -> Result<[u8; N], Error> {
+        let mut okm = [0u8; N];
+        self.expand(info, &mut okm)?;
+        Ok(okm)
+    }
+}
+
+// Extract and expand in one call
+pub fn hkdf(
+    algorithm: Algorithm,
+    salt: Option<&[u8]>,
+    ikm: &[u8],
+    info: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, Error> {
+    Hkdf::extract(algorithm, salt, ikm).expand_to_vec(info, length)
+}
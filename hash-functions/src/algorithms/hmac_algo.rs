@@ -0,0 +1,141 @@
+use std::io::{self, Write};
+
+use hmac::digest::KeyInit;
+use hmac::Mac;
+use sha2::{Sha224, Sha256, Sha384, Sha512, Sha512_256};
+use sha3::{Keccak224, Keccak256, Keccak384, Keccak512, Sha3_224, Sha3_256, Sha3_384, Sha3_512};
+
+use crate::elements::algorithm::Algorithm;
+use crate::utils::error::Error;
+
+// 80 bits, the shortest truncated tag RFC 2104 and SP 800-107 allow
+const MIN_TRUNCATED_TAG_LEN: usize = 10;
+
+// `hmac::Hmac` is generic over the hash function, so every algorithm is a different type.
+// The enum lets the caller pick the algorithm at runtime as `Hasher` does
+#[derive(Clone)]
+enum HmacState {
+    Sha224(hmac::Hmac<Sha224>),
+    Sha256(hmac::Hmac<Sha256>),
+    Sha384(hmac::Hmac<Sha384>),
+    Sha512(hmac::Hmac<Sha512>),
+    Sha512_256(hmac::Hmac<Sha512_256>),
+    Sha3_224(hmac::Hmac<Sha3_224>),
+    Sha3_256(hmac::Hmac<Sha3_256>),
+    Sha3_384(hmac::Hmac<Sha3_384>),
+    Sha3_512(hmac::Hmac<Sha3_512>),
+    Keccak224(hmac::Hmac<Keccak224>),
+    Keccak256(hmac::Hmac<Keccak256>),
+    Keccak384(hmac::Hmac<Keccak384>),
+    Keccak512(hmac::Hmac<Keccak512>),
+}
+
+// Runs the same expression whatever hash function is inside the state
+macro_rules! with_state {
+    ($state:expr, $mac:ident => $body:expr) => {
+        match $state {
+            HmacState::Sha224($mac) => $body,
+            HmacState::Sha256($mac) => $body,
+            HmacState::Sha384($mac) => $body,
+            HmacState::Sha512($mac) => $body,
+            HmacState::Sha512_256($mac) => $body,
+            HmacState::Sha3_224($mac) => $body,
+            HmacState::Sha3_256($mac) => $body,
+            HmacState::Sha3_384($mac) => $body,
+            HmacState::Sha3_512($mac) => $body,
+            HmacState::Keccak224($mac) => $body,
+            HmacState::Keccak256($mac) => $body,
+            HmacState::Keccak384($mac) => $body,
+            HmacState::Keccak512($mac) => $body,
+        }
+    };
+}
+
+// Keyed-hash message authentication code (RFC 2104) over any of the supported hash functions
+#[derive(Clone)]
+pub struct Hmac {
+    algorithm: Algorithm,
+    state: HmacState,
+}
+
+impl Hmac {
+    // HMAC accepts keys of any length: longer than the block size are hashed first
+    // and shorter are padded with zeros
+    pub fn new(algorithm: Algorithm, key: &[u8]) -> Hmac {
+        let state = match algorithm {
+            Algorithm::Sha224 => HmacState::Sha224(keyed(key)),
+            Algorithm::Sha256 => HmacState::Sha256(keyed(key)),
+            Algorithm::Sha384 => HmacState::Sha384(keyed(key)),
+            Algorithm::Sha512 => HmacState::Sha512(keyed(key)),
+            Algorithm::Sha512_256 => HmacState::Sha512_256(keyed(key)),
+            Algorithm::Sha3_224 => HmacState::Sha3_224(keyed(key)),
+            Algorithm::Sha3_256 => HmacState::Sha3_256(keyed(key)),
+            Algorithm::Sha3_384 => HmacState::Sha3_384(keyed(key)),
+            Algorithm::Sha3_512 => HmacState::Sha3_512(keyed(key)),
+            Algorithm::Keccak224 => HmacState::Keccak224(keyed(key)),
+            Algorithm::Keccak256 => HmacState::Keccak256(keyed(key)),
+            Algorithm::Keccak384 => HmacState::Keccak384(keyed(key)),
+            Algorithm::Keccak512 => HmacState::Keccak512(keyed(key)),
+        };
+        Hmac { algorithm, state }
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        with_state!(&mut self.state, mac => mac.update(data))
+    }
+
+    // The tag has the output length of the hash function
+    pub fn finalize(self) -> Vec<u8> {
+        with_state!(self.state, mac => mac.finalize().into_bytes().to_vec())
+    }
+
+    // Constant-time comparison of the full length tag. Never compare tags with `==`,
+    // the time it takes to find the first different byte leaks how much of a forged tag is right
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        with_state!(self.state, mac => mac.verify_slice(tag)).map_err(|_| Error::InvalidMac)
+    }
+
+    // Constant-time comparison of a tag truncated to its leftmost bytes (RFC 4231 test case 5).
+    // Tags shorter than half of the output or than 80 bits are rejected, as RFC 2104 recommends
+    pub fn verify_truncated(self, tag: &[u8]) -> Result<(), Error> {
+        if tag.len() < (self.algorithm.output_len() / 2).max(MIN_TRUNCATED_TAG_LEN) {
+            return Err(Error::InvalidMac);
+        }
+        with_state!(self.state, mac => mac.verify_truncated_left(tag)).map_err(|_| Error::InvalidMac)
+    }
+}
+
+// Allows to use `io::copy` to feed the MAC
+impl Write for Hmac {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// `new_from_slice` only fails for fixed key size MACs, never for HMAC
+fn keyed<M: KeyInit>(key: &[u8]) -> M {
+    M::new_from_slice(key).expect("HMAC accepts any key length")
+}
+
+// One-shot tag of a message
+pub fn hmac(algorithm: Algorithm, key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::new(algorithm, key);
+    mac.update(data);
+    mac.finalize()
+}
+
+// One-shot constant-time verification of a tag, e.g. the signature header of a webhook
+pub fn verify_hmac(algorithm: Algorithm, key: &[u8], data: &[u8], tag: &[u8]) -> Result<(), Error> {
+    let mut mac = Hmac::new(algorithm, key);
+    mac.update(data);
+    mac.verify(tag)
+}
@@ -0,0 +1,113 @@
+use std::io::{self, Write};
+
+use subtle::ConstantTimeEq;
+
+use crate::elements::algorithm::XofAlgorithm;
+use crate::utils::error::Error;
+use super::xof_algo::Xof;
+
+// Function name that NIST reserves for KMAC inside cSHAKE
+const KMAC_FUNCTION_NAME: &[u8] = b"KMAC";
+
+// Shortest tag `verify` accepts, shorter ones are within reach of guessing
+const MIN_TAG_LEN: usize = 16;
+
+// Keccak message authentication code (SP 800-185): KMAC128 on top of cSHAKE128 and KMAC256 on top
+// of cSHAKE256. Sponge functions do not suffer from length extension, so the key is just
+// absorbed before the message instead of the two passes of HMAC
+#[derive(Clone)]
+pub struct Kmac {
+    xof: Xof,
+}
+
+impl Kmac {
+    // newX = cSHAKE(bytepad(encode_string(K), rate) || X, "KMAC", S)
+    pub fn new(algorithm: XofAlgorithm, key: &[u8], customization: &[u8]) -> Kmac {
+        let mut xof = Xof::cshake(algorithm, KMAC_FUNCTION_NAME, customization);
+        xof.update(&bytepad(&encode_string(key), algorithm.rate()));
+        Kmac { xof }
+    }
+
+    pub fn algorithm(&self) -> XofAlgorithm {
+        self.xof.algorithm()
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.xof.update(data);
+    }
+
+    // The requested length is part of the input, so a shorter tag is not a prefix of a longer one
+    pub fn finalize(mut self, length: usize) -> Vec<u8> {
+        self.xof.update(&right_encode(length as u64 * 8));
+        self.xof.finalize(length)
+    }
+
+    // KMACXOF: the length is encoded as 0 and the output is a prefix of the same endless stream
+    pub fn finalize_xof(mut self, length: usize) -> Vec<u8> {
+        self.xof.update(&right_encode(0));
+        self.xof.finalize(length)
+    }
+
+    // Constant-time comparison of the tag, its length sets the KMAC output length.
+    // Tags under 16 bytes are rejected, an empty one would match any message
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        if tag.len() < MIN_TAG_LEN {
+            return Err(Error::InvalidMac);
+        }
+        let expected = self.finalize(tag.len());
+        match bool::from(expected.ct_eq(tag)) {
+            true => Ok(()),
+            false => Err(Error::InvalidMac),
+        }
+    }
+}
+
+// Allows to use `io::copy` to feed the MAC
+impl Write for Kmac {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// One-shot KMAC tag of `length` bytes
+pub fn kmac(algorithm: XofAlgorithm, key: &[u8], customization: &[u8], data: &[u8], length: usize) -> Vec<u8> {
+    let mut mac = Kmac::new(algorithm, key, customization);
+    mac.update(data);
+    mac.finalize(length)
+}
+
+// SP 800-185 encodings. The integer is written big-endian with the minimum number of bytes (at least one)
+// and the count of those bytes goes before (`left_encode`) or after (`right_encode`)
+fn integer_bytes(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let first = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len() - 1);
+    bytes[first..].to_vec()
+}
+
+fn left_encode(value: u64) -> Vec<u8> {
+    let bytes = integer_bytes(value);
+    [&[bytes.len() as u8], bytes.as_slice()].concat()
+}
+
+fn right_encode(value: u64) -> Vec<u8> {
+    let bytes = integer_bytes(value);
+    [bytes.as_slice(), &[bytes.len() as u8]].concat()
+}
+
+// Bit length of the string followed by the string, so it can be parsed back without ambiguity
+fn encode_string(data: &[u8]) -> Vec<u8> {
+    [left_encode(data.len() as u64 * 8).as_slice(), data].concat()
+}
+
+// Prefixes the width and pads with zeros to a multiple of it, so the key fills whole blocks
+fn bytepad(data: &[u8], width: usize) -> Vec<u8> {
+    let mut padded = left_encode(width as u64);
+    padded.extend_from_slice(data);
+    padded.resize(padded.len().div_ceil(width) * width, 0);
+    padded
+}
@@ -1,2 +1,10 @@
 pub mod sha2_algo;
-pub mod sha3_algo;
\ No newline at end of file
+pub mod sha3_algo;
+pub mod hasher;
+pub mod hmac_algo;
+pub mod hkdf_algo;
+pub mod xof_algo;
+pub mod kmac_algo;
+pub mod tagged_hash;
+pub mod resumable_hasher;
+pub mod drbg;
@@ -0,0 +1,224 @@
+use std::io::{self, Write};
+
+use sha2::digest::generic_array::GenericArray;
+use sha2::{compress256, compress512};
+
+use crate::attacks::length_extension::{padding, sponge_rate, too_long, MAX_MESSAGE_LEN};
+use crate::elements::multihash::HashFunction;
+use crate::elements::{algorithm::Algorithm, digest::Digest};
+use crate::utils::error::Error;
+
+// Initial values of FIPS 180-4 section 5.3
+const SHA224_IV: [u32; 8] = [
+    0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, 0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
+];
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+const SHA384_IV: [u64; 8] = [
+    0xcbbb9d5dc1059ed8, 0x629a292a367cd507, 0x9159015a3070dd17, 0x152fecd8f70e5939,
+    0x67332667ffc00b31, 0x8eb44a8768581511, 0xdb0c2e0d64f98fa7, 0x47b5481dbefa4fa4,
+];
+const SHA512_IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+const SHA512_256_IV: [u64; 8] = [
+    0x22312194fc2bf72c, 0x9f555fa3c84c64c2, 0x2393b86b6f53b151, 0x963877195940eabd,
+    0x96283ee2a88effe3, 0xbe5e1e2553863992, 0x2b0199fc2c85b8aa, 0x0eb72ddc81c52ca2,
+];
+
+// Bytes before the state in the serialized form: function code and processed length
+const HEADER_LEN: usize = 16;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum State {
+    Sha256([u32; 8]),
+    Sha512([u64; 8]),
+    Keccak([u64; 25]),
+}
+
+// Hasher whose state can be saved and restored, to hash a long stream across process restarts.
+// The RustCrypto hashers behind `Hasher` keep their state private, so this one runs the compression
+// functions itself: the chaining value of SHA-2 or the Keccak-f[1600] state, the bytes of the current
+// incomplete block and the number of bytes hashed so far are the whole state.
+//
+// The saved state reveals as much as the data hashed so far, keep it where the data is kept
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResumableHasher {
+    algorithm: Algorithm,
+    state: State,
+    buffer: Vec<u8>,
+    processed_len: u64,
+}
+
+impl ResumableHasher {
+    pub fn new(algorithm: Algorithm) -> ResumableHasher {
+        let state = match algorithm {
+            Algorithm::Sha224 => State::Sha256(SHA224_IV),
+            Algorithm::Sha256 => State::Sha256(SHA256_IV),
+            Algorithm::Sha384 => State::Sha512(SHA384_IV),
+            Algorithm::Sha512 => State::Sha512(SHA512_IV),
+            Algorithm::Sha512_256 => State::Sha512(SHA512_256_IV),
+            _ => State::Keccak([0; 25]),
+        };
+        ResumableHasher {
+            algorithm,
+            state,
+            buffer: Vec::with_capacity(block_len(algorithm)),
+            processed_len: 0,
+        }
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    // Bytes hashed so far, where an interrupted upload resumes
+    pub fn processed_len(&self) -> u64 {
+        self.processed_len
+    }
+
+    // Fails past 2^61 - 1 bytes, the most the padding can count
+    pub fn update(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.processed_len = (data.len() as u64)
+            .checked_add(self.processed_len)
+            .filter(|len| *len <= MAX_MESSAGE_LEN)
+            .ok_or_else(too_long)?;
+        self.absorb(data);
+        Ok(())
+    }
+
+    pub fn finalize(mut self) -> Digest {
+        let padding = padding(self.algorithm, self.processed_len).expect("length checked by update");
+        self.absorb(&padding);
+        let output_len = self.algorithm.output_len();
+        let bytes: Vec<u8> = match &self.state {
+            State::Sha256(state) => state.iter().flat_map(|word| word.to_be_bytes()).take(output_len).collect(),
+            State::Sha512(state) => state.iter().flat_map(|word| word.to_be_bytes()).take(output_len).collect(),
+            State::Keccak(state) => state.iter().flat_map(|lane| lane.to_le_bytes()).take(output_len).collect(),
+        };
+        Digest::new(self.algorithm, bytes).expect("hasher output length")
+    }
+
+    // function code (u64 BE) || processed length (u64 BE) || state || incomplete block.
+    // SHA-2 words are big-endian and Keccak lanes little-endian, as the functions read them
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + 200 + self.buffer.len());
+        bytes.extend_from_slice(&HashFunction::Fixed(self.algorithm).code().to_be_bytes());
+        bytes.extend_from_slice(&self.processed_len.to_be_bytes());
+        match &self.state {
+            State::Sha256(state) => state.iter().for_each(|word| bytes.extend_from_slice(&word.to_be_bytes())),
+            State::Sha512(state) => state.iter().for_each(|word| bytes.extend_from_slice(&word.to_be_bytes())),
+            State::Keccak(state) => state.iter().for_each(|lane| bytes.extend_from_slice(&lane.to_le_bytes())),
+        }
+        bytes.extend_from_slice(&self.buffer);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<ResumableHasher, Error> {
+        if bytes.len() < HEADER_LEN {
+            return Err(Error::InvalidDecodedLength { expected: HEADER_LEN, actual: bytes.len() });
+        }
+        let algorithm = match HashFunction::from_code(u64::from_be_bytes(bytes[..8].try_into().expect("8 bytes")))? {
+            HashFunction::Fixed(algorithm) => algorithm,
+            function => return Err(Error::UnsupportedAlgorithm(function.to_string())),
+        };
+        let processed_len = u64::from_be_bytes(bytes[8..16].try_into().expect("8 bytes"));
+        if processed_len > MAX_MESSAGE_LEN {
+            return Err(too_long());
+        }
+
+        let mut hasher = ResumableHasher::new(algorithm);
+        let state_len = match hasher.state {
+            State::Sha256(_) => 32,
+            State::Sha512(_) => 64,
+            State::Keccak(_) => 200,
+        };
+        let buffer_len = (processed_len % block_len(algorithm) as u64) as usize;
+        let expected = HEADER_LEN + state_len + buffer_len;
+        if bytes.len() != expected {
+            return Err(Error::InvalidDecodedLength { expected, actual: bytes.len() });
+        }
+
+        let (state, buffer) = bytes[HEADER_LEN..].split_at(state_len);
+        match &mut hasher.state {
+            State::Sha256(words) => {
+                for (word, chunk) in words.iter_mut().zip(state.chunks_exact(4)) {
+                    *word = u32::from_be_bytes(chunk.try_into().expect("4 bytes"));
+                }
+            }
+            State::Sha512(words) => {
+                for (word, chunk) in words.iter_mut().zip(state.chunks_exact(8)) {
+                    *word = u64::from_be_bytes(chunk.try_into().expect("8 bytes"));
+                }
+            }
+            State::Keccak(lanes) => {
+                for (lane, chunk) in lanes.iter_mut().zip(state.chunks_exact(8)) {
+                    *lane = u64::from_le_bytes(chunk.try_into().expect("8 bytes"));
+                }
+            }
+        }
+        hasher.buffer.extend_from_slice(buffer);
+        hasher.processed_len = processed_len;
+        Ok(hasher)
+    }
+
+    // Completes the buffered block, compresses the whole blocks of `data` in place and buffers the rest
+    fn absorb(&mut self, data: &[u8]) {
+        let block_len = block_len(self.algorithm);
+        let mut data = data;
+        if !self.buffer.is_empty() {
+            let taken = data.len().min(block_len - self.buffer.len());
+            self.buffer.extend_from_slice(&data[..taken]);
+            data = &data[taken..];
+            if self.buffer.len() < block_len {
+                return;
+            }
+            let block = std::mem::take(&mut self.buffer);
+            self.compress(&block);
+            self.buffer = block;
+            self.buffer.clear();
+        }
+
+        let whole = data.len() - data.len() % block_len;
+        for block in data[..whole].chunks_exact(block_len) {
+            self.compress(block);
+        }
+        self.buffer.extend_from_slice(&data[whole..]);
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        match &mut self.state {
+            State::Sha256(state) => compress256(state, &[GenericArray::clone_from_slice(block)]),
+            State::Sha512(state) => compress512(state, &[GenericArray::clone_from_slice(block)]),
+            State::Keccak(state) => {
+                for (lane, chunk) in state.iter_mut().zip(block.chunks_exact(8)) {
+                    *lane ^= u64::from_le_bytes(chunk.try_into().expect("8 bytes"));
+                }
+                keccak::f1600(state);
+            }
+        }
+    }
+}
+
+// Allows to use `io::copy` to feed the hasher
+impl Write for ResumableHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Bytes per compression: the SHA-2 block or the rate of the sponge
+fn block_len(algorithm: Algorithm) -> usize {
+    match algorithm {
+        Algorithm::Sha224 | Algorithm::Sha256 => 64,
+        Algorithm::Sha384 | Algorithm::Sha512 | Algorithm::Sha512_256 => 128,
+        _ => sponge_rate(algorithm),
+    }
+}
@@ -28,7 +28,7 @@ pub fn hash2_encryption_algorithm() {
     println!("Base64-encoded hash: {}", base64_hash);
 
     // hashed array format to HEX
-    let hex_hash = hex::encode(&message_hash);
+    let hex_hash = hex::encode(message_hash);
     println!("HEX-encoded hash: {}", hex_hash);
 }
 
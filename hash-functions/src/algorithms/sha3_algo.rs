@@ -14,6 +14,6 @@ pub fn hash3_encryption_algorithm() {
     println!("Base64-encoded hash: {}", base64_hash);
 
     // hashed array format to HEX
-    let hex_hash = hex::encode(&hashed_message);
+    let hex_hash = hex::encode(hashed_message);
     println!("HEX-encoded hash: {}", hex_hash);
 }
@@ -0,0 +1,109 @@
+use std::io::{self, Write};
+
+use sha3::digest::core_api::CoreWrapper;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{CShake128, CShake128Core, CShake256, CShake256Core, Shake128, Shake256};
+
+use crate::elements::algorithm::XofAlgorithm;
+
+#[derive(Clone)]
+enum XofState {
+    Shake128(Shake128),
+    Shake256(Shake256),
+    CShake128(CShake128),
+    CShake256(CShake256),
+}
+
+// Incremental SHAKE or cSHAKE (SP 800-185) hasher. Unlike `Hasher`, the output length
+// is chosen when finalizing and the output can be read as a stream
+#[derive(Clone)]
+pub struct Xof {
+    algorithm: XofAlgorithm,
+    state: XofState,
+}
+
+impl Xof {
+    pub fn shake(algorithm: XofAlgorithm) -> Xof {
+        let state = match algorithm {
+            XofAlgorithm::Shake128 => XofState::Shake128(Shake128::default()),
+            XofAlgorithm::Shake256 => XofState::Shake256(Shake256::default()),
+        };
+        Xof { algorithm, state }
+    }
+
+    // cSHAKE adds domain separation: the same input with another customization string gives an
+    // unrelated output. `function_name` is reserved for functions defined by NIST (e.g. "KMAC"),
+    // applications should leave it empty and use `customization`.
+    // With both strings empty cSHAKE is exactly SHAKE
+    pub fn cshake(algorithm: XofAlgorithm, function_name: &[u8], customization: &[u8]) -> Xof {
+        let state = match algorithm {
+            XofAlgorithm::Shake128 => XofState::CShake128(CoreWrapper::from_core(
+                CShake128Core::new_with_function_name(function_name, customization),
+            )),
+            XofAlgorithm::Shake256 => XofState::CShake256(CoreWrapper::from_core(
+                CShake256Core::new_with_function_name(function_name, customization),
+            )),
+        };
+        Xof { algorithm, state }
+    }
+
+    pub fn algorithm(&self) -> XofAlgorithm {
+        self.algorithm
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.state {
+            XofState::Shake128(xof) => xof.update(data),
+            XofState::Shake256(xof) => xof.update(data),
+            XofState::CShake128(xof) => xof.update(data),
+            XofState::CShake256(xof) => xof.update(data),
+        }
+    }
+
+    // Reader over the endless output. Reading 32 bytes and then 32 more gives
+    // the same bytes as reading 64 at once
+    pub fn finalize_xof(self) -> Box<dyn XofReader> {
+        match self.state {
+            XofState::Shake128(xof) => Box::new(xof.finalize_xof()),
+            XofState::Shake256(xof) => Box::new(xof.finalize_xof()),
+            XofState::CShake128(xof) => Box::new(xof.finalize_xof()),
+            XofState::CShake256(xof) => Box::new(xof.finalize_xof()),
+        }
+    }
+
+    pub fn finalize_into(self, output: &mut [u8]) {
+        self.finalize_xof().read(output);
+    }
+
+    pub fn finalize(self, length: usize) -> Vec<u8> {
+        let mut output = vec![0u8; length];
+        self.finalize_into(&mut output);
+        output
+    }
+}
+
+// Allows to use `io::copy` to feed the hasher
+impl Write for Xof {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// One-shot SHAKE output of `length` bytes
+pub fn shake(algorithm: XofAlgorithm, data: &[u8], length: usize) -> Vec<u8> {
+    let mut xof = Xof::shake(algorithm);
+    xof.update(data);
+    xof.finalize(length)
+}
+
+// One-shot cSHAKE output of `length` bytes with an application customization string
+pub fn cshake(algorithm: XofAlgorithm, customization: &[u8], data: &[u8], length: usize) -> Vec<u8> {
+    let mut xof = Xof::cshake(algorithm, b"", customization);
+    xof.update(data);
+    xof.finalize(length)
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+use crate::algorithms::hasher::digest;
+use crate::elements::algorithm::Algorithm;
+use crate::utils::error::Error;
+
+// Truncations shorter than this are found by listing a few hundred values, longer than 64 bits
+// do not fit the `u64` points and would take years anyway
+pub const MIN_BITS: u32 = 8;
+pub const MAX_BITS: u32 = 64;
+
+// A chain longer than this many times the expected distance between distinguished points
+// is probably stuck in a cycle without any, it is dropped
+const MAX_CHAIN_FACTOR: u64 = 20;
+
+// Collision search on the first `bits` bits of a hash with Pollard's rho and distinguished points
+// (van Oorschot and Wiener). Every chain walks x -> H(x) truncated until it reaches a point whose
+// `distinguished_bits` lowest bits are zero, and only that end point is stored. Two chains ending on the
+// same point have merged, walking them again from their starts finds the two inputs with the same output.
+// The memory is bounded by `max_table_entries` instead of the ~2^(bits/2) values of a plain birthday table
+#[derive(Clone, Debug)]
+pub struct CollisionSearch {
+    pub algorithm: Algorithm,
+    pub bits: u32,
+    pub distinguished_bits: u32,
+    pub max_table_entries: usize,
+    // The search fails after this many hash evaluations
+    pub max_evaluations: u64,
+    // Start points are derived from the seed, so a search can be replayed
+    pub seed: u64,
+}
+
+// Two different inputs whose digests share the first `bits` bits
+#[derive(Clone, Debug)]
+pub struct Collision {
+    pub algorithm: Algorithm,
+    pub bits: u32,
+    pub first: Vec<u8>,
+    pub second: Vec<u8>,
+    // The shared truncated digest
+    pub value: u64,
+    // Hash evaluations of the search, walking the chains and locating the collision
+    pub evaluations: u64,
+    // sqrt(pi / 2 * 2^bits), the average for a random function
+    pub expected_evaluations: f64,
+    pub chains: u64,
+    pub table_entries: usize,
+}
+
+impl CollisionSearch {
+    // Distinguished points every 2^(bits/4) steps: a few thousand table entries for 48 bits
+    pub fn new(algorithm: Algorithm, bits: u32) -> Result<CollisionSearch, Error> {
+        if !(MIN_BITS..=MAX_BITS).contains(&bits) {
+            return Err(Error::InvalidParameter(format!(
+                "truncation must be between {} and {} bits, got {}",
+                MIN_BITS, MAX_BITS, bits
+            )));
+        }
+        Ok(CollisionSearch {
+            algorithm,
+            bits,
+            distinguished_bits: bits / 4,
+            max_table_entries: 1 << 20,
+            max_evaluations: (expected_evaluations(bits) * 64.0) as u64,
+            seed: 0,
+        })
+    }
+
+    pub fn run(&self) -> Result<Collision, Error> {
+        // Beyond half the truncation a chain is expected to collide before it reaches a distinguished
+        // point, and the chain length limit would no longer fit a `u64`
+        if self.distinguished_bits > self.bits / 2 {
+            return Err(Error::InvalidParameter(format!(
+                "at most {} distinguished bits with a {}-bit truncation, got {}",
+                self.bits / 2,
+                self.bits,
+                self.distinguished_bits
+            )));
+        }
+
+        let mut walker = Walker { search: self, evaluations: 0 };
+        let mut table: HashMap<u64, (u64, u64)> = HashMap::new();
+        let max_chain = MAX_CHAIN_FACTOR << self.distinguished_bits;
+        let mut seed = self.seed;
+        let mut chains = 0;
+
+        while walker.evaluations < self.max_evaluations {
+            let start = splitmix64(&mut seed) & walker.mask();
+            chains += 1;
+            let Some((end, length)) = walker.walk_to_distinguished(start, max_chain) else {
+                continue;
+            };
+
+            match table.get(&end).copied() {
+                Some((other_start, other_length)) if other_start != start => {
+                    if let Some((first, second)) = walker.locate(start, length, other_start, other_length) {
+                        return Ok(Collision {
+                            algorithm: self.algorithm,
+                            bits: self.bits,
+                            first: first.to_be_bytes().to_vec(),
+                            second: second.to_be_bytes().to_vec(),
+                            value: truncated_digest(self.algorithm, self.bits, &first.to_be_bytes()),
+                            evaluations: walker.evaluations,
+                            expected_evaluations: expected_evaluations(self.bits),
+                            chains,
+                            table_entries: table.len(),
+                        });
+                    }
+                }
+                Some(_) => (),
+                // Once full, new chains are still checked against the stored ones
+                None if table.len() < self.max_table_entries => {
+                    table.insert(end, (start, length));
+                }
+                None => (),
+            }
+        }
+
+        Err(Error::SearchExhausted { evaluations: walker.evaluations })
+    }
+}
+
+impl Collision {
+    // Actual work divided by the expected one
+    pub fn work_ratio(&self) -> f64 {
+        self.evaluations as f64 / self.expected_evaluations
+    }
+}
+
+// sqrt(pi / 2 * 2^bits): average number of random values drawn before two are equal
+pub fn expected_evaluations(bits: u32) -> f64 {
+    (PI / 2.0 * 2f64.powi(bits as i32)).sqrt()
+}
+
+// First `bits` bits of the digest of `input`, the search hashes the 8 byte big-endian encoding of the points
+pub fn truncated_digest(algorithm: Algorithm, bits: u32, input: &[u8]) -> u64 {
+    let digest = digest(algorithm, input);
+    let head = u64::from_be_bytes(digest.as_bytes()[..8].try_into().expect("digests have 8 bytes at least"));
+    head >> (64 - bits)
+}
+
+struct Walker<'a> {
+    search: &'a CollisionSearch,
+    evaluations: u64,
+}
+
+impl Walker<'_> {
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.search.bits)
+    }
+
+    fn step(&mut self, point: u64) -> u64 {
+        self.evaluations += 1;
+        truncated_digest(self.search.algorithm, self.search.bits, &point.to_be_bytes())
+    }
+
+    fn is_distinguished(&self, point: u64) -> bool {
+        point & ((1 << self.search.distinguished_bits) - 1) == 0
+    }
+
+    // End point and number of steps, or nothing if the chain is too long
+    fn walk_to_distinguished(&mut self, start: u64, max_chain: u64) -> Option<(u64, u64)> {
+        let mut point = start;
+        for length in 1..=max_chain {
+            point = self.step(point);
+            if self.is_distinguished(point) {
+                return Some((point, length));
+            }
+        }
+        None
+    }
+
+    // Walks both chains from the same distance to their common end point. The points before the first
+    // common one are the collision. If the chains meet at a start point, one chain is part of the other
+    fn locate(&mut self, mut a: u64, a_length: u64, mut b: u64, b_length: u64) -> Option<(u64, u64)> {
+        for _ in b_length..a_length {
+            a = self.step(a);
+        }
+        for _ in a_length..b_length {
+            b = self.step(b);
+        }
+
+        while a != b {
+            let (next_a, next_b) = (self.step(a), self.step(b));
+            if next_a == next_b {
+                return Some((a, b));
+            }
+            (a, b) = (next_a, next_b);
+        }
+        None
+    }
+}
+
+// Small generator for the start points, statistical quality is enough here
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,178 @@
+use sha2::digest::generic_array::GenericArray;
+use sha2::{compress256, compress512};
+use subtle::ConstantTimeEq;
+
+use crate::algorithms::hasher::digest;
+use crate::elements::{algorithm::Algorithm, digest::Digest};
+use crate::utils::error::Error;
+
+// Bytes of the Keccak-f[1600] state
+const KECCAK_STATE_LEN: usize = 200;
+
+// The SHA-2 padding holds the length in bits in a u64
+pub(crate) const MAX_MESSAGE_LEN: u64 = u64::MAX / 8;
+
+// Message and tag built by the attacker without knowing the secret.
+// `message` is the original message, the padding the hash appended to it and the extension
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Forgery {
+    pub message: Vec<u8>,
+    pub tag: Digest,
+}
+
+// The naive MAC this attack breaks: H(secret || message). Use HMAC or KMAC instead
+pub fn secret_prefix_mac(algorithm: Algorithm, secret: &[u8], message: &[u8]) -> Digest {
+    digest(algorithm, &[secret, message].concat())
+}
+
+pub fn verify_secret_prefix_mac(algorithm: Algorithm, secret: &[u8], message: &[u8], tag: &Digest) -> Result<(), Error> {
+    let expected = secret_prefix_mac(algorithm, secret, message);
+    match expected.algorithm() == tag.algorithm() && bool::from(expected.as_bytes().ct_eq(tag.as_bytes())) {
+        true => Ok(()),
+        false => Err(Error::InvalidMac),
+    }
+}
+
+// Bytes the hash appends to a message of `message_len` bytes before the last compression:
+// `0x80`, zeros up to the length field and the length in bits, big-endian.
+// SHA-3 and Keccak use the sponge padding of their rate. Fails past 2^61 - 1 bytes for SHA-2
+pub fn padding(algorithm: Algorithm, message_len: u64) -> Result<Vec<u8>, Error> {
+    let (block_len, length_len) = match merkle_damgard_block(algorithm) {
+        Some(block) => block,
+        None => return Ok(sponge_padding(algorithm, message_len)),
+    };
+    let bit_len = message_len.checked_mul(8).ok_or_else(too_long)?;
+    let zeros = (block_len - 1 - length_len) as i64 - (message_len % block_len as u64) as i64;
+    let zeros = zeros.rem_euclid(block_len as i64) as usize;
+
+    let mut padding = vec![0x80];
+    padding.resize(1 + zeros, 0);
+    // The length field is 64 or 128 bits, a `u64` length takes the lowest 8 bytes of it
+    padding.resize(1 + zeros + length_len - 8, 0);
+    padding.extend_from_slice(&bit_len.to_be_bytes());
+    Ok(padding)
+}
+
+// Forges H(secret || message || padding || extension) from H(secret || message) and the secret length only.
+// SHA-256 and SHA-512 output their whole internal state, so the attacker loads the digest as the state
+// and keeps compressing. The secret length is rarely known, the attacker tries every plausible one.
+//
+// The same attempt on SHA-3 or Keccak cannot work: the digest is only part of the 1600-bit sponge state
+// and the capacity is never output. The unknown bytes are guessed as zeros, so the tag is wrong.
+// Truncated SHA-2 variants hide part of their state as well and are refused
+pub fn forge(tag: &Digest, secret_len: usize, message: &[u8], extension: &[u8]) -> Result<Forgery, Error> {
+    let algorithm = tag.algorithm();
+    let prefix_len = checked_len(secret_len as u64, message.len())?;
+    let glue = padding(algorithm, prefix_len)?;
+    let processed_len = checked_len(prefix_len, glue.len())?;
+    let forged_message = [message, &glue, extension].concat();
+
+    let bytes = match algorithm {
+        Algorithm::Sha256 => extend_sha256(tag.as_bytes(), processed_len, extension)?,
+        Algorithm::Sha512 => extend_sha512(tag.as_bytes(), processed_len, extension)?,
+        Algorithm::Sha3_224
+        | Algorithm::Sha3_256
+        | Algorithm::Sha3_384
+        | Algorithm::Sha3_512
+        | Algorithm::Keccak224
+        | Algorithm::Keccak256
+        | Algorithm::Keccak384
+        | Algorithm::Keccak512 => extend_sponge(algorithm, tag.as_bytes(), extension),
+        _ => return Err(Error::UnsupportedAlgorithm(algorithm.to_string())),
+    };
+
+    Ok(Forgery {
+        message: forged_message,
+        tag: Digest::new(algorithm, bytes)?,
+    })
+}
+
+// Length of the message once `added` more bytes are hashed, at most what the SHA-2 padding can count
+fn checked_len(len: u64, added: usize) -> Result<u64, Error> {
+    len.checked_add(added as u64).filter(|len| *len <= MAX_MESSAGE_LEN).ok_or_else(too_long)
+}
+
+pub(crate) fn too_long() -> Error {
+    Error::InvalidParameter(format!("at most {} bytes can be hashed", MAX_MESSAGE_LEN))
+}
+
+// Block and length field sizes of the SHA-2 functions that output their full state
+fn merkle_damgard_block(algorithm: Algorithm) -> Option<(usize, usize)> {
+    match algorithm {
+        Algorithm::Sha224 | Algorithm::Sha256 => Some((64, 8)),
+        Algorithm::Sha384 | Algorithm::Sha512 | Algorithm::Sha512_256 => Some((128, 16)),
+        _ => None,
+    }
+}
+
+fn extend_sha256(tag: &[u8], processed_len: u64, extension: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut state = [0u32; 8];
+    for (word, bytes) in state.iter_mut().zip(tag.chunks_exact(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+
+    let tail = [extension, &padding(Algorithm::Sha256, checked_len(processed_len, extension.len())?)?].concat();
+    let blocks: Vec<_> = tail.chunks_exact(64).map(GenericArray::clone_from_slice).collect();
+    compress256(&mut state, &blocks);
+
+    Ok(state.iter().flat_map(|word| word.to_be_bytes()).collect())
+}
+
+fn extend_sha512(tag: &[u8], processed_len: u64, extension: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut state = [0u64; 8];
+    for (word, bytes) in state.iter_mut().zip(tag.chunks_exact(8)) {
+        *word = u64::from_be_bytes(bytes.try_into().unwrap());
+    }
+
+    let tail = [extension, &padding(Algorithm::Sha512, checked_len(processed_len, extension.len())?)?].concat();
+    let blocks: Vec<_> = tail.chunks_exact(128).map(GenericArray::clone_from_slice).collect();
+    compress512(&mut state, &blocks);
+
+    Ok(state.iter().flat_map(|word| word.to_be_bytes()).collect())
+}
+
+// The best an attacker can do with a sponge: the digest gives the first bytes of the state,
+// the other `200 - output_len` bytes are unknown
+fn extend_sponge(algorithm: Algorithm, tag: &[u8], extension: &[u8]) -> Vec<u8> {
+    let mut bytes = [0u8; KECCAK_STATE_LEN];
+    bytes[..tag.len()].copy_from_slice(tag);
+    let mut state = [0u64; 25];
+    for (lane, chunk) in state.iter_mut().zip(bytes.chunks_exact(8)) {
+        *lane = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+
+    let rate = sponge_rate(algorithm);
+    let tail = [extension, &sponge_padding(algorithm, extension.len() as u64)].concat();
+    for block in tail.chunks_exact(rate) {
+        for (lane, chunk) in state.iter_mut().zip(block.chunks_exact(8)) {
+            *lane ^= u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        keccak::f1600(&mut state);
+    }
+
+    state
+        .iter()
+        .flat_map(|lane| lane.to_le_bytes())
+        .take(algorithm.output_len())
+        .collect()
+}
+
+// Twice the output length is reserved for the capacity
+pub(crate) fn sponge_rate(algorithm: Algorithm) -> usize {
+    KECCAK_STATE_LEN - 2 * algorithm.output_len()
+}
+
+// pad10*1 with the domain bits: `0x06` for SHA-3, `0x01` for the original Keccak
+fn sponge_padding(algorithm: Algorithm, message_len: u64) -> Vec<u8> {
+    let rate = sponge_rate(algorithm);
+    let first = match algorithm {
+        Algorithm::Keccak224 | Algorithm::Keccak256 | Algorithm::Keccak384 | Algorithm::Keccak512 => 0x01,
+        _ => 0x06,
+    };
+    let len = rate - (message_len % rate as u64) as usize;
+
+    let mut padding = vec![0u8; len];
+    padding[0] = first;
+    padding[len - 1] |= 0x80;
+    padding
+}
@@ -0,0 +1,2 @@
+pub mod birthday;
+pub mod length_extension;
@@ -0,0 +1,194 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::utils::error::Error;
+
+// The hash functions that can be selected at runtime.
+// SHA-2 family comes from the `sha2` crate, SHA-3 and Keccak families from the `sha3` crate
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    // SHA-512 with a different initial value, truncated to 256 bits
+    Sha512_256,
+    Sha3_224,
+    Sha3_256,
+    Sha3_384,
+    Sha3_512,
+    // Keccak as submitted to the SHA-3 competition, before FIPS 202 changed the padding
+    // from `0x01` to `0x06`. Used by Ethereum, where it is often called SHA3 by mistake
+    Keccak224,
+    Keccak256,
+    Keccak384,
+    Keccak512,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 13] = [
+        Algorithm::Sha224,
+        Algorithm::Sha256,
+        Algorithm::Sha384,
+        Algorithm::Sha512,
+        Algorithm::Sha512_256,
+        Algorithm::Sha3_224,
+        Algorithm::Sha3_256,
+        Algorithm::Sha3_384,
+        Algorithm::Sha3_512,
+        Algorithm::Keccak224,
+        Algorithm::Keccak256,
+        Algorithm::Keccak384,
+        Algorithm::Keccak512,
+    ];
+
+    // Name as written in the FIPS 180-4 and FIPS 202 standards and in the Keccak submission
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Sha224 => "SHA-224",
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Sha384 => "SHA-384",
+            Algorithm::Sha512 => "SHA-512",
+            Algorithm::Sha512_256 => "SHA-512/256",
+            Algorithm::Sha3_224 => "SHA3-224",
+            Algorithm::Sha3_256 => "SHA3-256",
+            Algorithm::Sha3_384 => "SHA3-384",
+            Algorithm::Sha3_512 => "SHA3-512",
+            Algorithm::Keccak224 => "Keccak-224",
+            Algorithm::Keccak256 => "Keccak-256",
+            Algorithm::Keccak384 => "Keccak-384",
+            Algorithm::Keccak512 => "Keccak-512",
+        }
+    }
+
+    // Tag of the BSD checksum format, the one printed by `sha256sum --tag` and `sha3sum --tag`.
+    // Keccak has no coreutils tool, its tag follows the one of `sha3sum`
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Algorithm::Sha224 => "SHA224",
+            Algorithm::Sha256 => "SHA256",
+            Algorithm::Sha384 => "SHA384",
+            Algorithm::Sha512 => "SHA512",
+            Algorithm::Sha512_256 => "SHA512/256",
+            Algorithm::Sha3_224 => "SHA3-224",
+            Algorithm::Sha3_256 => "SHA3-256",
+            Algorithm::Sha3_384 => "SHA3-384",
+            Algorithm::Sha3_512 => "SHA3-512",
+            Algorithm::Keccak224 => "KECCAK-224",
+            Algorithm::Keccak256 => "KECCAK-256",
+            Algorithm::Keccak384 => "KECCAK-384",
+            Algorithm::Keccak512 => "KECCAK-512",
+        }
+    }
+
+    // Length of the digest in bytes
+    pub fn output_len(&self) -> usize {
+        match self {
+            Algorithm::Sha224 | Algorithm::Sha3_224 | Algorithm::Keccak224 => 28,
+            Algorithm::Sha256 | Algorithm::Sha512_256 | Algorithm::Sha3_256 | Algorithm::Keccak256 => 32,
+            Algorithm::Sha384 | Algorithm::Sha3_384 | Algorithm::Keccak384 => 48,
+            Algorithm::Sha512 | Algorithm::Sha3_512 | Algorithm::Keccak512 => 64,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = Error;
+
+    // Accepts the standard name and the usual spellings of it: `SHA-256`, `sha256`, `sha3_256`...
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .collect::<String>()
+            .to_lowercase();
+
+        match normalized.as_str() {
+            "sha224" => Ok(Algorithm::Sha224),
+            "sha256" => Ok(Algorithm::Sha256),
+            "sha384" => Ok(Algorithm::Sha384),
+            "sha512" => Ok(Algorithm::Sha512),
+            "sha512/256" | "sha512256" => Ok(Algorithm::Sha512_256),
+            "sha3224" => Ok(Algorithm::Sha3_224),
+            "sha3256" => Ok(Algorithm::Sha3_256),
+            "sha3384" => Ok(Algorithm::Sha3_384),
+            "sha3512" => Ok(Algorithm::Sha3_512),
+            "keccak224" => Ok(Algorithm::Keccak224),
+            "keccak256" => Ok(Algorithm::Keccak256),
+            "keccak384" => Ok(Algorithm::Keccak384),
+            "keccak512" => Ok(Algorithm::Keccak512),
+            _ => Err(Error::UnknownAlgorithm(s.to_string())),
+        }
+    }
+}
+
+// Extendable-output functions (FIPS 202): the caller chooses the length of the output.
+// They are kept apart from `Algorithm` because HMAC and HKDF are only defined for fixed output hashes
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum XofAlgorithm {
+    Shake128,
+    Shake256,
+}
+
+impl XofAlgorithm {
+    pub const ALL: [XofAlgorithm; 2] = [XofAlgorithm::Shake128, XofAlgorithm::Shake256];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            XofAlgorithm::Shake128 => "SHAKE128",
+            XofAlgorithm::Shake256 => "SHAKE256",
+        }
+    }
+
+    // Security strength in bits against collisions and preimages
+    pub fn security_bits(&self) -> usize {
+        match self {
+            XofAlgorithm::Shake128 => 128,
+            XofAlgorithm::Shake256 => 256,
+        }
+    }
+
+    // Bytes absorbed per Keccak-f[1600] permutation, used by the SP 800-185 `bytepad` encoding
+    pub fn rate(&self) -> usize {
+        match self {
+            XofAlgorithm::Shake128 => 168,
+            XofAlgorithm::Shake256 => 136,
+        }
+    }
+
+    // Output length in bytes when the caller does not choose one: twice the security strength,
+    // so collisions cost as much as a preimage
+    pub fn default_output_len(&self) -> usize {
+        self.security_bits() / 4
+    }
+}
+
+impl fmt::Display for XofAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for XofAlgorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .collect::<String>()
+            .to_lowercase();
+
+        match normalized.as_str() {
+            "shake128" => Ok(XofAlgorithm::Shake128),
+            "shake256" => Ok(XofAlgorithm::Shake256),
+            _ => Err(Error::UnknownAlgorithm(s.to_string())),
+        }
+    }
+}
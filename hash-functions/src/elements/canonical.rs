@@ -0,0 +1,596 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::algorithms::hasher::Hasher;
+use crate::elements::{algorithm::Algorithm, digest::Digest};
+use crate::utils::error::Error;
+
+// Nesting allowed by the parser, deeper documents would overflow the stack
+const MAX_DEPTH: usize = 128;
+
+// Integral numbers in [-2^63, 2^63) are integers, whether written `1`, `1.0` or `1e0`
+const INTEGER_BOUND: f64 = 9_223_372_036_854_775_808.0;
+
+// Structured value, the data model shared by JSON and CBOR. Objects have text keys, as in JSON.
+//
+// Numbers are normalized so that a value has one form only: an integral `Float` is the same
+// value as the `Integer`, and both encodings write them the same way
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    // CBOR only, JSON has no byte strings
+    Bytes(Vec<u8>),
+    Array(Vec<Value>),
+    Object(BTreeMap<String, Value>),
+}
+
+// Canonical serializations. The digest of a value starts with the name of the encoding,
+// so the JCS and the CBOR digests of the same value are unrelated
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CanonicalEncoding {
+    // JSON Canonicalization Scheme (RFC 8785)
+    Jcs,
+    // Core deterministic encoding of CBOR (RFC 8949 section 4.2.1)
+    Cbor,
+}
+
+impl CanonicalEncoding {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CanonicalEncoding::Jcs => "JCS",
+            CanonicalEncoding::Cbor => "CBOR",
+        }
+    }
+}
+
+impl fmt::Display for CanonicalEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Value {
+    // Parses an I-JSON document (RFC 7493): duplicate keys, lone surrogates and numbers beyond
+    // the double range are rejected
+    pub fn from_json(text: &str) -> Result<Value, Error> {
+        let mut parser = Parser { bytes: text.as_bytes(), position: 0 };
+        parser.skip_whitespace();
+        let value = parser.value(0)?;
+        parser.skip_whitespace();
+        match parser.position == parser.bytes.len() {
+            true => Ok(value),
+            false => Err(parser.error("trailing characters")),
+        }
+    }
+
+    // RFC 8785: object members sorted by the UTF-16 code units of their keys, no whitespace,
+    // numbers written as ECMAScript does and minimal string escaping.
+    // Byte strings, non-finite floats and integers a double cannot hold have no JSON form
+    pub fn to_jcs(&self) -> Result<String, Error> {
+        let mut output = String::new();
+        write_jcs(self, &mut output)?;
+        Ok(output)
+    }
+
+    // Definite lengths, shortest integer and float heads, map keys sorted by their encoding.
+    // Integral floats are written as integers
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+        write_cbor(self, &mut output);
+        output
+    }
+
+    pub fn encode(&self, encoding: CanonicalEncoding) -> Result<Vec<u8>, Error> {
+        match encoding {
+            CanonicalEncoding::Jcs => self.to_jcs().map(String::into_bytes),
+            CanonicalEncoding::Cbor => Ok(self.to_cbor()),
+        }
+    }
+
+    // H(encoding name length || encoding name || canonical encoding)
+    pub fn digest(&self, algorithm: Algorithm, encoding: CanonicalEncoding) -> Result<Digest, Error> {
+        let encoded = self.encode(encoding)?;
+        let mut hasher = Hasher::new(algorithm);
+        hasher.update(&(encoding.name().len() as u64).to_be_bytes());
+        hasher.update(encoding.name().as_bytes());
+        hasher.update(&encoded);
+        Ok(hasher.finalize())
+    }
+}
+
+impl FromStr for Value {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Value::from_json(s)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+// Digest of a JSON document, the same for every serialization of the same object
+pub fn json_digest(algorithm: Algorithm, json: &str) -> Result<Digest, Error> {
+    Value::from_json(json)?.digest(algorithm, CanonicalEncoding::Jcs)
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn value(&mut self, depth: usize) -> Result<Value, Error> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("too deeply nested"));
+        }
+        match self.peek() {
+            Some(b'{') => self.object(depth),
+            Some(b'[') => self.array(depth),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b't') => self.literal("true", Value::Bool(true)),
+            Some(b'f') => self.literal("false", Value::Bool(false)),
+            Some(b'n') => self.literal("null", Value::Null),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end")),
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> Result<Value, Error> {
+        self.position += 1;
+        let mut members = BTreeMap::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(Value::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected a key"));
+            }
+            let key_position = self.position;
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            self.skip_whitespace();
+            let value = self.value(depth + 1)?;
+            if members.insert(key, value).is_some() {
+                return Err(Error::InvalidJson { position: key_position, reason: "duplicate key" });
+            }
+            self.skip_whitespace();
+            match self.next() {
+                Some(b',') => continue,
+                Some(b'}') => return Ok(Value::Object(members)),
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<Value, Error> {
+        self.position += 1;
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Value::Array(elements));
+        }
+        loop {
+            self.skip_whitespace();
+            elements.push(self.value(depth + 1)?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(b',') => continue,
+                Some(b']') => return Ok(Value::Array(elements)),
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.position += 1;
+        let mut string = String::new();
+        loop {
+            // Copies the run of plain characters at once, the input is valid UTF-8 already
+            let start = self.position;
+            while matches!(self.peek(), Some(byte) if byte != b'"' && byte != b'\\' && byte >= 0x20) {
+                self.position += 1;
+            }
+            string.push_str(std::str::from_utf8(&self.bytes[start..self.position]).expect("input is a str"));
+
+            match self.next() {
+                Some(b'"') => return Ok(string),
+                Some(b'\\') => {
+                    let escaped = match self.next() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode_escape()?,
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    string.push(escaped);
+                }
+                Some(_) => {
+                    return Err(Error::InvalidJson { position: self.position - 1, reason: "unescaped control character" });
+                }
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    // \uXXXX, a high surrogate must be followed by the escape of a low one
+    fn unicode_escape(&mut self) -> Result<char, Error> {
+        let start = self.position - 2;
+        let lone_surrogate = Error::InvalidJson { position: start, reason: "lone surrogate" };
+        let unit = self.hex_unit()?;
+        let code = match unit {
+            0xd800..=0xdbff => {
+                if self.next() != Some(b'\\') || self.next() != Some(b'u') {
+                    return Err(lone_surrogate);
+                }
+                let low = self.hex_unit()?;
+                if !(0xdc00..=0xdfff).contains(&low) {
+                    return Err(lone_surrogate);
+                }
+                0x10000 + ((u32::from(unit) - 0xd800) << 10) + (u32::from(low) - 0xdc00)
+            }
+            0xdc00..=0xdfff => return Err(lone_surrogate),
+            _ => u32::from(unit),
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid code point"))
+    }
+
+    fn hex_unit(&mut self) -> Result<u16, Error> {
+        let digits = self.bytes.get(self.position..self.position + 4).ok_or_else(|| self.error("short escape"))?;
+        let digits = std::str::from_utf8(digits).map_err(|_| self.error("invalid escape"))?;
+        let unit = u16::from_str_radix(digits, 16).map_err(|_| self.error("invalid escape"))?;
+        self.position += 4;
+        Ok(unit)
+    }
+
+    // -?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)?
+    fn number(&mut self) -> Result<Value, Error> {
+        let start = self.position;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.position += 1,
+            Some(b'1'..=b'9') => self.digits(),
+            _ => return Err(self.error("invalid number")),
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.error("invalid number"));
+            }
+            self.digits();
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.error("invalid number"));
+            }
+            self.digits();
+        }
+
+        let text = std::str::from_utf8(&self.bytes[start..self.position]).expect("ASCII digits");
+        // Integers a double holds exactly, larger ones are rounded to the nearest double as JSON does
+        match text.parse::<i64>() {
+            Ok(integer) if integer.unsigned_abs() <= 1 << 53 => return Ok(Value::Integer(integer)),
+            _ => {}
+        }
+        let float: f64 = text.parse().map_err(|_| Error::InvalidJson { position: start, reason: "invalid number" })?;
+        if !float.is_finite() {
+            return Err(Error::InvalidJson { position: start, reason: "number out of range" });
+        }
+        Ok(normalize_float(float))
+    }
+
+    fn digits(&mut self) {
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.position += 1;
+        }
+    }
+
+    fn literal(&mut self, literal: &'static str, value: Value) -> Result<Value, Error> {
+        match self.bytes[self.position..].starts_with(literal.as_bytes()) {
+            true => {
+                self.position += literal.len();
+                Ok(value)
+            }
+            false => Err(self.error("invalid literal")),
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), Error> {
+        match self.next() == Some(byte) {
+            true => Ok(()),
+            false => Err(self.error("unexpected character")),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek();
+        self.position += 1;
+        byte
+    }
+
+    fn error(&self, reason: &'static str) -> Error {
+        Error::InvalidJson { position: self.position.min(self.bytes.len()), reason }
+    }
+}
+
+fn normalize_float(float: f64) -> Value {
+    match float.fract() == 0.0 && (-INTEGER_BOUND..INTEGER_BOUND).contains(&float) {
+        true => Value::Integer(float as i64),
+        false => Value::Float(float),
+    }
+}
+
+fn write_jcs(value: &Value, output: &mut String) -> Result<(), Error> {
+    match value {
+        Value::Null => output.push_str("null"),
+        Value::Bool(value) => output.push_str(if *value { "true" } else { "false" }),
+        Value::Integer(integer) => {
+            // Above 2^53 only the integers a double holds exactly have a JSON form
+            if (*integer as f64) as i128 != i128::from(*integer) {
+                return Err(Error::InvalidParameter(format!("{} has no exact JSON form", integer)));
+            }
+            // and ECMAScript writes those with their shortest digits: 2^60 is 1152921504606847000
+            match integer.unsigned_abs() > 1 << 53 {
+                true => output.push_str(&ecmascript_number(*integer as f64)),
+                false => output.push_str(&integer.to_string()),
+            }
+        }
+        Value::Float(float) => match normalize_float(*float) {
+            Value::Integer(integer) => write_jcs(&Value::Integer(integer), output)?,
+            _ if !float.is_finite() => {
+                return Err(Error::InvalidParameter(format!("{} has no JSON form", float)));
+            }
+            _ => output.push_str(&ecmascript_number(*float)),
+        },
+        Value::String(string) => write_jcs_string(string, output),
+        Value::Bytes(_) => return Err(Error::InvalidParameter("byte strings have no JSON form".to_string())),
+        Value::Array(elements) => {
+            output.push('[');
+            for (i, element) in elements.iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                write_jcs(element, output)?;
+            }
+            output.push(']');
+        }
+        Value::Object(members) => {
+            let mut members: Vec<_> = members.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            output.push('{');
+            for (i, (key, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                write_jcs_string(key, output);
+                output.push(':');
+                write_jcs(value, output)?;
+            }
+            output.push('}');
+        }
+    }
+    Ok(())
+}
+
+// Only the quote, the backslash and the control characters are escaped
+fn write_jcs_string(string: &str, output: &mut String) {
+    output.push('"');
+    for character in string.chars() {
+        match character {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\u{8}' => output.push_str("\\b"),
+            '\t' => output.push_str("\\t"),
+            '\n' => output.push_str("\\n"),
+            '\u{c}' => output.push_str("\\f"),
+            '\r' => output.push_str("\\r"),
+            '\u{0}'..='\u{1f}' => output.push_str(&format!("\\u{:04x}", character as u32)),
+            _ => output.push(character),
+        }
+    }
+    output.push('"');
+}
+
+// Number::toString of ECMAScript (ECMA-262 section 6.1.6.1.20) for a finite, non-integral double.
+// `{:e}` gives the length of the shortest digits that read back as the same double. When two such
+// digit strings are equally close, ECMAScript takes the even one, as the exact rounding of `{:.*e}` does
+fn ecmascript_number(float: f64) -> String {
+    let shortest = format!("{:e}", float.abs());
+    let precision = shortest.split_once('e').expect("scientific notation").0.replace('.', "").len() - 1;
+    let scientific = format!("{:.*e}", precision, float.abs());
+    let (mantissa, exponent) = scientific.split_once('e').expect("scientific notation");
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    // float = 0.digits * 10^n
+    let n = exponent.parse::<i32>().expect("exponent") + 1;
+
+    let mut output = String::new();
+    if float < 0.0 {
+        output.push('-');
+    }
+    if k <= n && n <= 21 {
+        output.push_str(&digits);
+        output.push_str(&"0".repeat((n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        output.push_str(&digits[..n as usize]);
+        output.push('.');
+        output.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        output.push_str("0.");
+        output.push_str(&"0".repeat(-n as usize));
+        output.push_str(&digits);
+    } else {
+        output.push_str(&digits[..1]);
+        if k > 1 {
+            output.push('.');
+            output.push_str(&digits[1..]);
+        }
+        output.push_str(&format!("e{}{}", if n > 0 { '+' } else { '-' }, (n - 1).abs()));
+    }
+    output
+}
+
+fn write_cbor(value: &Value, output: &mut Vec<u8>) {
+    match value {
+        Value::Null => output.push(0xf6),
+        Value::Bool(value) => output.push(if *value { 0xf5 } else { 0xf4 }),
+        Value::Integer(integer) if *integer >= 0 => write_cbor_head(0, *integer as u64, output),
+        Value::Integer(integer) => write_cbor_head(1, !*integer as u64, output),
+        Value::Float(float) => match normalize_float(*float) {
+            Value::Integer(integer) => write_cbor(&Value::Integer(integer), output),
+            _ => write_cbor_float(*float, output),
+        },
+        Value::String(string) => {
+            write_cbor_head(3, string.len() as u64, output);
+            output.extend_from_slice(string.as_bytes());
+        }
+        Value::Bytes(bytes) => {
+            write_cbor_head(2, bytes.len() as u64, output);
+            output.extend_from_slice(bytes);
+        }
+        Value::Array(elements) => {
+            write_cbor_head(4, elements.len() as u64, output);
+            elements.iter().for_each(|element| write_cbor(element, output));
+        }
+        Value::Object(members) => {
+            // Sorted by the bytes of the encoded keys: shorter keys first, then byte by byte
+            let mut members: Vec<(Vec<u8>, &Value)> = members
+                .iter()
+                .map(|(key, value)| (Value::String(key.clone()).to_cbor(), value))
+                .collect();
+            members.sort_by(|(a, _), (b, _)| a.cmp(b));
+            write_cbor_head(5, members.len() as u64, output);
+            for (key, value) in members {
+                output.extend_from_slice(&key);
+                write_cbor(value, output);
+            }
+        }
+    }
+}
+
+// Major type and argument in the fewest bytes
+fn write_cbor_head(major: u8, argument: u64, output: &mut Vec<u8>) {
+    let major = major << 5;
+    match argument {
+        0..=23 => output.push(major | argument as u8),
+        24..=0xff => output.extend_from_slice(&[major | 24, argument as u8]),
+        0x100..=0xffff => {
+            output.push(major | 25);
+            output.extend_from_slice(&(argument as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            output.push(major | 26);
+            output.extend_from_slice(&(argument as u32).to_be_bytes());
+        }
+        _ => {
+            output.push(major | 27);
+            output.extend_from_slice(&argument.to_be_bytes());
+        }
+    }
+}
+
+// The shortest of half, single and double precision that holds the value exactly.
+// Every NaN is written as the quiet NaN 0x7e00
+fn write_cbor_float(float: f64, output: &mut Vec<u8>) {
+    if float.is_nan() {
+        output.extend_from_slice(&[0xf9, 0x7e, 0x00]);
+        return;
+    }
+    let single = float as f32;
+    if f64::from(single) != float {
+        output.push(0xfb);
+        output.extend_from_slice(&float.to_bits().to_be_bytes());
+    } else if let Some(half) = half_bits(single) {
+        output.push(0xf9);
+        output.extend_from_slice(&half.to_be_bytes());
+    } else {
+        output.push(0xfa);
+        output.extend_from_slice(&single.to_bits().to_be_bytes());
+    }
+}
+
+// Bits of the half precision float equal to `single`, if there is one
+fn half_bits(single: f32) -> Option<u16> {
+    let bits = single.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    match exponent {
+        0xff if mantissa == 0 => Some(sign | 0x7c00),
+        0 if mantissa == 0 => Some(sign),
+        // Subnormal singles are far below the smallest half
+        0 => None,
+        _ => {
+            let exponent = exponent - 127;
+            if (-14..=15).contains(&exponent) && mantissa & 0x1fff == 0 {
+                return Some(sign | (((exponent + 15) as u16) << 10) | (mantissa >> 13) as u16);
+            }
+            // Subnormal half: significand * 2^(exponent - 23) = m * 2^-24
+            let shift = -(exponent + 1);
+            let significand = mantissa | 0x80_0000;
+            match (14..=23).contains(&shift) && significand & ((1 << shift) - 1) == 0 {
+                true => Some(sign | (significand >> shift) as u16),
+                false => None,
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::fmt;
+
+use crate::utils::error::Error;
+use super::algorithm::Algorithm;
+use super::encoding::Encoding;
+
+// The output of a hash function together with the algorithm that produced it.
+// Keeping both avoids comparing digests of different algorithms by accident
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Digest {
+    algorithm: Algorithm,
+    bytes: Vec<u8>,
+}
+
+impl Digest {
+    pub fn new(algorithm: Algorithm, bytes: Vec<u8>) -> Result<Digest, Error> {
+        if bytes.len() != algorithm.output_len() {
+            return Err(Error::InvalidDigestLength {
+                expected: algorithm.output_len(),
+                actual: bytes.len(),
+            });
+        }
+        Ok(Digest { algorithm, bytes })
+    }
+
+    pub fn from_hex(algorithm: Algorithm, hex_digest: &str) -> Result<Digest, Error> {
+        let bytes = hex::decode(hex_digest).map_err(|_| Error::InvalidHexString)?;
+        Digest::new(algorithm, bytes)
+    }
+
+    // Strict decoding, the text must hold exactly the output length of the algorithm
+    pub fn decode(algorithm: Algorithm, encoding: Encoding, text: &str) -> Result<Digest, Error> {
+        let bytes = encoding.decode(text)?;
+        Digest::new(algorithm, bytes)
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn encode(&self, encoding: Encoding) -> String {
+        encoding.encode(&self.bytes)
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.encode(Encoding::Hex)
+    }
+
+    pub fn to_base64(&self) -> String {
+        self.encode(Encoding::Base64)
+    }
+}
+
+impl AsRef<[u8]> for Digest {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+// Lowercase hex, the usual way to print a digest
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl fmt::Debug for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Digest({}, {})", self.algorithm, self.to_hex())
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+use std::str::FromStr;
+
+use base64ct::{Base64, Base64Unpadded, Base64UrlUnpadded, Encoding as _};
+use data_encoding::BASE32;
+
+use crate::utils::error::Error;
+
+// Text encodings for digests, keys and salts. Decoding is strict: only the canonical form of
+// each encoding is accepted, so one byte string has exactly one valid text. Hex is the exception,
+// it is decoded case-insensitively since digests are often written in uppercase
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    // Lowercase hexadecimal, uppercase is accepted when decoding
+    Hex,
+    // RFC 4648 section 4, with `=` padding
+    Base64,
+    // RFC 4648 section 4, without padding. The alphabet of PHC strings and argon2 salts
+    Base64Unpadded,
+    // RFC 4648 section 5, without padding as in JWT. Safe inside URLs and file names
+    Base64Url,
+    // RFC 4648 section 6, uppercase with `=` padding
+    Base32,
+    // Bitcoin alphabet, without the characters that look alike (0, O, I and l)
+    Base58,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 6] = [
+        Encoding::Hex,
+        Encoding::Base64,
+        Encoding::Base64Unpadded,
+        Encoding::Base64Url,
+        Encoding::Base32,
+        Encoding::Base58,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Hex => "hex",
+            Encoding::Base64 => "base64",
+            Encoding::Base64Unpadded => "base64-unpadded",
+            Encoding::Base64Url => "base64url",
+            Encoding::Base32 => "base32",
+            Encoding::Base58 => "base58",
+        }
+    }
+
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        match self {
+            Encoding::Hex => hex::encode(bytes),
+            Encoding::Base64 => Base64::encode_string(bytes),
+            Encoding::Base64Unpadded => Base64Unpadded::encode_string(bytes),
+            Encoding::Base64Url => Base64UrlUnpadded::encode_string(bytes),
+            Encoding::Base32 => BASE32.encode(bytes),
+            Encoding::Base58 => bs58::encode(bytes).into_string(),
+        }
+    }
+
+    pub fn decode(&self, text: &str) -> Result<Vec<u8>, Error> {
+        let decoded = match self {
+            Encoding::Hex => hex::decode(text).ok(),
+            Encoding::Base64 => Base64::decode_vec(text).ok(),
+            Encoding::Base64Unpadded => Base64Unpadded::decode_vec(text).ok(),
+            Encoding::Base64Url => Base64UrlUnpadded::decode_vec(text).ok(),
+            Encoding::Base32 => BASE32.decode(text.as_bytes()).ok(),
+            // Base58 has no padding or length rule, a leading `1` is a leading zero byte
+            Encoding::Base58 => bs58::decode(text).into_vec().ok(),
+        };
+        decoded.ok_or(Error::InvalidEncoding(self.name()))
+    }
+
+    // Decodes and checks that the text holds exactly `length` bytes
+    pub fn decode_exact(&self, text: &str, length: usize) -> Result<Vec<u8>, Error> {
+        let bytes = self.decode(text)?;
+        if bytes.len() != length {
+            return Err(Error::InvalidDecodedLength {
+                expected: length,
+                actual: bytes.len(),
+            });
+        }
+        Ok(bytes)
+    }
+
+    // Decodes into a fixed size array, for keys, salts and other values of known length
+    pub fn decode_array<const N: usize>(&self, text: &str) -> Result<[u8; N], Error> {
+        let bytes = self.decode_exact(text, N)?;
+        Ok(bytes.try_into().expect("length already checked"))
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .collect::<String>()
+            .to_lowercase();
+
+        match normalized.as_str() {
+            "hex" | "base16" => Ok(Encoding::Hex),
+            "base64" => Ok(Encoding::Base64),
+            "base64unpadded" | "base64nopad" => Ok(Encoding::Base64Unpadded),
+            "base64url" => Ok(Encoding::Base64Url),
+            "base32" => Ok(Encoding::Base32),
+            "base58" => Ok(Encoding::Base58),
+            _ => Err(Error::UnknownEncoding(s.to_string())),
+        }
+    }
+}
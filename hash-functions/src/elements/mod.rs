@@ -0,0 +1,5 @@
+pub mod algorithm;
+pub mod digest;
+pub mod encoding;
+pub mod multihash;
+pub mod canonical;
@@ -0,0 +1,225 @@
+use std::fmt;
+
+use subtle::ConstantTimeEq;
+
+use crate::algorithms::hasher::digest;
+use crate::algorithms::xof_algo::shake;
+use crate::utils::error::Error;
+use super::algorithm::{Algorithm, XofAlgorithm};
+use super::digest::Digest;
+
+// Varints longer than 9 bytes are rejected by the multiformats unsigned-varint specification
+const MAX_VARINT_LEN: usize = 9;
+
+// Hash function identified by a multicodec code: a fixed output hash or an extendable-output function
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HashFunction {
+    Fixed(Algorithm),
+    Xof(XofAlgorithm),
+}
+
+// Self-describing digest (https://multiformats.io/multihash/):
+// varint(function code) || varint(digest length) || digest.
+// The function travels with the digest, so stored digests can still be verified after the default
+// algorithm changes. A fixed output digest may be truncated, the length says how many bytes are kept
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Multihash {
+    function: HashFunction,
+    digest: Vec<u8>,
+}
+
+impl HashFunction {
+    // Code of the multicodec table
+    pub fn code(&self) -> u64 {
+        match self {
+            HashFunction::Fixed(Algorithm::Sha256) => 0x12,
+            HashFunction::Fixed(Algorithm::Sha512) => 0x13,
+            HashFunction::Fixed(Algorithm::Sha3_512) => 0x14,
+            HashFunction::Fixed(Algorithm::Sha3_384) => 0x15,
+            HashFunction::Fixed(Algorithm::Sha3_256) => 0x16,
+            HashFunction::Fixed(Algorithm::Sha3_224) => 0x17,
+            HashFunction::Xof(XofAlgorithm::Shake128) => 0x18,
+            HashFunction::Xof(XofAlgorithm::Shake256) => 0x19,
+            HashFunction::Fixed(Algorithm::Keccak224) => 0x1a,
+            HashFunction::Fixed(Algorithm::Keccak256) => 0x1b,
+            HashFunction::Fixed(Algorithm::Keccak384) => 0x1c,
+            HashFunction::Fixed(Algorithm::Keccak512) => 0x1d,
+            HashFunction::Fixed(Algorithm::Sha384) => 0x20,
+            HashFunction::Fixed(Algorithm::Sha224) => 0x1013,
+            HashFunction::Fixed(Algorithm::Sha512_256) => 0x1015,
+        }
+    }
+
+    pub fn from_code(code: u64) -> Result<HashFunction, Error> {
+        let all = Algorithm::ALL
+            .into_iter()
+            .map(HashFunction::Fixed)
+            .chain(XofAlgorithm::ALL.into_iter().map(HashFunction::Xof));
+        for function in all {
+            if function.code() == code {
+                return Ok(function);
+            }
+        }
+        Err(Error::UnknownMultihashCode(code))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashFunction::Fixed(algorithm) => algorithm.name(),
+            HashFunction::Xof(algorithm) => algorithm.name(),
+        }
+    }
+}
+
+impl fmt::Display for HashFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Multihash {
+    // Fixed output digests can be truncated but not extended
+    pub fn new(function: HashFunction, digest: Vec<u8>) -> Result<Multihash, Error> {
+        if digest.is_empty() {
+            return Err(Error::InvalidMultihash("empty digest".to_string()));
+        }
+        if let HashFunction::Fixed(algorithm) = function {
+            if digest.len() > algorithm.output_len() {
+                return Err(Error::InvalidDigestLength {
+                    expected: algorithm.output_len(),
+                    actual: digest.len(),
+                });
+            }
+        }
+        Ok(Multihash { function, digest })
+    }
+
+    // Multihash of `data` with the full output of `algorithm`
+    pub fn hash(algorithm: Algorithm, data: &[u8]) -> Multihash {
+        Multihash::from(&digest(algorithm, data))
+    }
+
+    pub fn function(&self) -> HashFunction {
+        self.function
+    }
+
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+
+    // The digest is complete only for a fixed output function without truncation
+    pub fn to_digest(&self) -> Result<Digest, Error> {
+        match self.function {
+            HashFunction::Fixed(algorithm) => Digest::new(algorithm, self.digest.clone()),
+            HashFunction::Xof(algorithm) => Err(Error::InvalidMultihash(format!("{} has no fixed output", algorithm))),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = encode_varint(self.function.code());
+        bytes.extend(encode_varint(self.digest.len() as u64));
+        bytes.extend_from_slice(&self.digest);
+        bytes
+    }
+
+    // Parses a multihash that takes the whole input
+    pub fn from_bytes(bytes: &[u8]) -> Result<Multihash, Error> {
+        let (multihash, read) = Multihash::read(bytes)?;
+        if read != bytes.len() {
+            return Err(Error::InvalidMultihash(format!("{} trailing bytes", bytes.len() - read)));
+        }
+        Ok(multihash)
+    }
+
+    // Parses the multihash at the start of the input and returns the number of bytes read,
+    // for formats where something follows the digest
+    pub fn read(bytes: &[u8]) -> Result<(Multihash, usize), Error> {
+        let (code, code_len) = decode_varint(bytes)?;
+        let (length, length_len) = decode_varint(&bytes[code_len..])?;
+        let function = HashFunction::from_code(code)?;
+
+        let start = code_len + length_len;
+        let available = (bytes.len() - start) as u64;
+        if length > available {
+            return Err(Error::InvalidMultihash(format!(
+                "digest length is {} bytes but only {} are left",
+                length, available
+            )));
+        }
+        let end = start + length as usize;
+        Ok((Multihash::new(function, bytes[start..end].to_vec())?, end))
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    pub fn from_hex(hex_multihash: &str) -> Result<Multihash, Error> {
+        let bytes = hex::decode(hex_multihash).map_err(|_| Error::InvalidHexString)?;
+        Multihash::from_bytes(&bytes)
+    }
+
+    // Hashes `data` again with the function of the multihash and compares, in constant time,
+    // as many bytes as the stored digest has
+    pub fn verify(&self, data: &[u8]) -> Result<(), Error> {
+        let computed = match self.function {
+            HashFunction::Fixed(algorithm) => digest(algorithm, data).into_bytes(),
+            HashFunction::Xof(algorithm) => shake(algorithm, data, self.digest.len()),
+        };
+        match bool::from(computed[..self.digest.len()].ct_eq(&self.digest)) {
+            true => Ok(()),
+            false => Err(Error::DigestMismatch),
+        }
+    }
+}
+
+impl From<&Digest> for Multihash {
+    fn from(digest: &Digest) -> Multihash {
+        Multihash {
+            function: HashFunction::Fixed(digest.algorithm()),
+            digest: digest.as_bytes().to_vec(),
+        }
+    }
+}
+
+// Hex of the encoded multihash, e.g. `1220...` for SHA-256
+impl fmt::Display for Multihash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl fmt::Debug for Multihash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Multihash({}, {})", self.function, hex::encode(&self.digest))
+    }
+}
+
+// Unsigned LEB128: 7 bits per byte, least significant group first, high bit set when more bytes follow
+fn encode_varint(mut value: u64) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+    bytes
+}
+
+// Only the minimal encoding is accepted, otherwise the same multihash would have several encodings
+fn decode_varint(bytes: &[u8]) -> Result<(u64, usize), Error> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().take(MAX_VARINT_LEN).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            if *byte == 0 && i > 0 {
+                return Err(Error::InvalidMultihash("varint is not minimally encoded".to_string()));
+            }
+            return Ok((value, i + 1));
+        }
+    }
+    match bytes.len() < MAX_VARINT_LEN {
+        true => Err(Error::InvalidMultihash("truncated varint".to_string())),
+        false => Err(Error::InvalidMultihash("varint is longer than 9 bytes".to_string())),
+    }
+}
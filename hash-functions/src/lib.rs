@@ -1 +1,7 @@
-pub mod algorithms;
\ No newline at end of file
+pub mod algorithms;
+pub mod attacks;
+pub mod elements;
+pub mod protocols;
+pub mod signatures;
+pub mod structures;
+pub mod utils;
@@ -1,9 +1,623 @@
-pub mod algorithms;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::str::FromStr;
 
-use algorithms::sha2_algo::hash2_encryption_algorithm;
-use algorithms::sha3_algo::hash3_encryption_algorithm;
+use clap::{Parser, Subcommand};
+use hash_functions::algorithms::hasher::digest_reader;
+use hash_functions::algorithms::hmac_algo::{hmac, verify_hmac};
+use hash_functions::algorithms::kmac_algo::Kmac;
+use hash_functions::algorithms::xof_algo::Xof;
+use hash_functions::attacks::birthday::CollisionSearch;
+use hash_functions::attacks::length_extension::{forge, secret_prefix_mac, verify_secret_prefix_mac};
+use hash_functions::elements::algorithm::{Algorithm, XofAlgorithm};
+use hash_functions::elements::digest::Digest;
+use hash_functions::structures::directory_manifest::DirectoryManifest;
+use hash_functions::utils::checksum::{
+    check_entry, format_line, format_xof_line, parse_line, CheckStatus, ChecksumFormat,
+};
+use hash_functions::utils::error::Error;
+use walkdir::WalkDir;
 
-fn main() {
-    hash2_encryption_algorithm();
-    hash3_encryption_algorithm();
+// Name used by coreutils for the standard input
+const STDIN_NAME: &str = "-";
+// Longest secret `--max-secret-len` tries, one forgery is printed per length
+const MAX_GUESSED_SECRET_LEN: usize = 4096;
+
+/// Print or check SHA-2, SHA-3 and Keccak checksums in the sha256sum/sha3sum format
+#[derive(Parser)]
+#[command(name = "hash-functions", version, args_conflicts_with_subcommands = true)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Hash algorithm: sha224, sha256, sha384, sha512, sha512/256, sha3-224, sha3-256, sha3-384, sha3-512,
+    /// keccak-224, keccak-256, keccak-384, keccak-512, shake128 or shake256
+    #[arg(short, long, default_value = "sha256")]
+    algorithm: HashAlgorithm,
+
+    /// Digest length in bits for shake128 and shake256, a multiple of 8.
+    /// By default twice the security strength: 256 and 512 bits
+    #[arg(short, long)]
+    length: Option<usize>,
+
+    /// Create a BSD-style checksum
+    #[arg(long)]
+    tag: bool,
+
+    /// Mark the files as read in binary mode (`*` before the name)
+    #[arg(short, long)]
+    binary: bool,
+
+    /// Read checksums from the FILEs and check them
+    #[arg(short, long)]
+    check: bool,
+
+    /// With --check, do not print OK for each successfully verified file
+    #[arg(long, requires = "check")]
+    quiet: bool,
+
+    /// With --check, exit non-zero for improperly formatted checksum lines
+    #[arg(long, requires = "check")]
+    strict: bool,
+
+    /// Print a manifest of each directory: the digest, size and path of every file, sorted by path,
+    /// followed by the root digest of the whole tree. Files are hashed in parallel
+    #[arg(short, long, conflicts_with_all = ["check", "tag", "binary"])]
+    manifest: bool,
+
+    /// With --manifest, only print the root digest of each directory
+    #[arg(long, requires = "manifest")]
+    root_only: bool,
+
+    /// Files or directories to hash. With no FILE, or when FILE is -, read standard input
+    files: Vec<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Forge a SHA256(secret || message) tag for a longer message without the secret.
+    /// With --secret, run the whole demonstration against SHA-2, SHA-3 and HMAC
+    LengthExtension(LengthExtensionArgs),
+    /// Find two inputs whose digests share their first bits, with Pollard's rho
+    Birthday(BirthdayArgs),
+    /// KMAC128 or KMAC256 tag of each FILE
+    Kmac(KmacArgs),
+    /// cSHAKE128 or cSHAKE256 output of each FILE
+    Cshake(CshakeArgs),
+}
+
+#[derive(clap::Args)]
+#[command(group = clap::ArgGroup::new("secret_length").args(["secret_len", "max_secret_len"]))]
+struct LengthExtensionArgs {
+    /// Message that was authenticated
+    #[arg(long)]
+    message: String,
+
+    /// Data to append to the message
+    #[arg(long)]
+    extension: String,
+
+    /// Tag of the message in hex, computed as H(secret || message)
+    #[arg(long, requires = "secret_length", required_unless_present = "secret")]
+    tag: Option<String>,
+
+    /// Length of the secret in bytes
+    #[arg(long)]
+    secret_len: Option<usize>,
+
+    /// Longest secret when its length is not known: one forgery per length from 0 up to this one, at most 4096
+    #[arg(long)]
+    max_secret_len: Option<usize>,
+
+    /// Hash function of the tag: sha256 or sha512 (sha3 and keccak show the failed attempt)
+    #[arg(short, long, default_value = "sha256")]
+    algorithm: Algorithm,
+
+    /// Secret of the demonstration: sign the message, forge the extension and verify it
+    #[arg(long, conflicts_with_all = ["tag", "secret_length"])]
+    secret: Option<String>,
+}
+
+#[derive(clap::Args)]
+struct BirthdayArgs {
+    /// Number of leading digest bits that must collide, between 8 and 64
+    #[arg(long, default_value_t = 32)]
+    bits: u32,
+
+    /// Hash function to attack
+    #[arg(short, long, default_value = "sha256")]
+    algorithm: Algorithm,
+
+    /// Seed of the chain start points
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Low bits that must be zero for a point to be stored, at most bits / 2 [default: bits / 4]
+    #[arg(long)]
+    distinguished_bits: Option<u32>,
+
+    /// Maximum number of distinguished points kept in memory
+    #[arg(long)]
+    max_table_entries: Option<usize>,
+}
+
+#[derive(clap::Args)]
+struct KmacArgs {
+    /// Key in hex
+    #[arg(long)]
+    key: String,
+
+    /// Customization string that separates the tags of different applications
+    #[arg(short, long, default_value = "")]
+    customization: String,
+
+    /// shake128 for KMAC128, shake256 for KMAC256
+    #[arg(short, long, default_value = "shake256")]
+    algorithm: XofAlgorithm,
+
+    /// Tag length in bits, a multiple of 8. By default twice the security strength: 256 and 512 bits
+    #[arg(short, long)]
+    length: Option<usize>,
+
+    /// KMACXOF: the tag does not depend on its length, a shorter tag is a prefix of a longer one
+    #[arg(long)]
+    xof: bool,
+
+    /// Files to authenticate. With no FILE, or when FILE is -, read standard input
+    files: Vec<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct CshakeArgs {
+    /// Customization string that separates the outputs of different applications
+    #[arg(short, long, default_value = "")]
+    customization: String,
+
+    /// Function name, reserved for functions defined by NIST on top of cSHAKE
+    #[arg(long, default_value = "")]
+    function_name: String,
+
+    /// shake128 for cSHAKE128, shake256 for cSHAKE256
+    #[arg(short, long, default_value = "shake256")]
+    algorithm: XofAlgorithm,
+
+    /// Output length in bits, a multiple of 8. By default twice the security strength: 256 and 512 bits
+    #[arg(short, long)]
+    length: Option<usize>,
+
+    /// Files to hash. With no FILE, or when FILE is -, read standard input
+    files: Vec<PathBuf>,
+}
+
+// Value of `--algorithm`: a fixed output hash or an extendable-output function
+#[derive(Copy, Clone)]
+enum HashAlgorithm {
+    Fixed(Algorithm),
+    Xof(XofAlgorithm),
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<Algorithm>() {
+            Ok(algorithm) => Ok(HashAlgorithm::Fixed(algorithm)),
+            Err(_) => s.parse::<XofAlgorithm>().map(HashAlgorithm::Xof),
+        }
+    }
+}
+
+// Counters of a manifest verification, reported as warnings like coreutils does
+#[derive(Default)]
+struct CheckSummary {
+    verified: usize,
+    failed: usize,
+    missing: usize,
+    malformed: usize,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match &args.command {
+        Some(Command::LengthExtension(attack)) => return length_extension(attack),
+        Some(Command::Birthday(attack)) => return birthday(attack),
+        Some(Command::Kmac(kmac)) => return kmac_files(kmac),
+        Some(Command::Cshake(cshake)) => return cshake_files(cshake),
+        None => (),
+    }
+    if let Err(message) = validate_args(&args) {
+        eprintln!("hash-functions: {}", message);
+        return ExitCode::FAILURE;
+    }
+
+    let inputs = match args.files.is_empty() {
+        true => vec![PathBuf::from(STDIN_NAME)],
+        false => args.files.clone(),
+    };
+
+    let succeeded = match (args.check, args.manifest) {
+        (true, _) => check_manifests(&args, &inputs),
+        (_, true) => print_directory_manifests(&args, &inputs),
+        _ => hash_files(&args, &inputs),
+    };
+
+    match succeeded {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+// Combinations that clap cannot express
+fn validate_args(args: &Args) -> Result<(), String> {
+    match (args.algorithm, args.length) {
+        (HashAlgorithm::Fixed(algorithm), Some(_)) => {
+            return Err(format!("--length is not supported with {}", algorithm))
+        }
+        (HashAlgorithm::Xof(_), Some(length)) if length == 0 || length % 8 != 0 => {
+            return Err(format!("invalid length: {}, it must be a positive multiple of 8", length))
+        }
+        _ => (),
+    }
+    if let (HashAlgorithm::Xof(algorithm), true) = (args.algorithm, args.check) {
+        return Err(format!("--check is not supported with {}", algorithm));
+    }
+    if let (HashAlgorithm::Xof(algorithm), true) = (args.algorithm, args.manifest) {
+        return Err(format!("--manifest is not supported with {}", algorithm));
+    }
+    Ok(())
+}
+
+fn hash_files(args: &Args, inputs: &[PathBuf]) -> bool {
+    let format = match args.tag {
+        true => ChecksumFormat::Bsd,
+        false => ChecksumFormat::Gnu,
+    };
+
+    let mut succeeded = true;
+    for input in inputs {
+        let files = match list_files(input) {
+            Ok(files) => files,
+            Err(e) => {
+                report_error(input, &e);
+                succeeded = false;
+                continue;
+            }
+        };
+        for file in files {
+            match checksum_line(args, &file, format) {
+                Ok(line) => println!("{}", line),
+                Err(e) => {
+                    report_error(&file, &e);
+                    succeeded = false;
+                }
+            }
+        }
+    }
+    succeeded
+}
+
+// Re-hashes every file listed in the manifests. GNU lines are checked with `--algorithm`,
+// BSD tagged lines with the algorithm of their tag
+fn check_manifests(args: &Args, manifests: &[PathBuf]) -> bool {
+    // `validate_args` already rejected the extendable-output functions
+    let HashAlgorithm::Fixed(algorithm) = args.algorithm else {
+        return false;
+    };
+
+    let mut succeeded = true;
+    for manifest in manifests {
+        let content = match read_manifest(manifest) {
+            Ok(content) => content,
+            Err(e) => {
+                report_error(manifest, &Error::Io(e));
+                succeeded = false;
+                continue;
+            }
+        };
+
+        let mut summary = CheckSummary::default();
+        for line in content.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = match parse_line(line, algorithm) {
+                Ok(entry) => entry,
+                Err(_) => {
+                    summary.malformed += 1;
+                    continue;
+                }
+            };
+            let status = check_entry(&entry);
+            match status {
+                CheckStatus::Ok => summary.verified += 1,
+                CheckStatus::Failed => summary.failed += 1,
+                CheckStatus::Missing => summary.missing += 1,
+            }
+            if status != CheckStatus::Ok || !args.quiet {
+                println!("{}: {}", entry.name, status.as_str());
+            }
+        }
+
+        succeeded &= report_summary(manifest, &summary, args.strict);
+    }
+    succeeded
+}
+
+// Manifest of each directory, with the root digest on a last line starting with `#`
+// so tools reading the entries can skip it as a comment
+fn print_directory_manifests(args: &Args, directories: &[PathBuf]) -> bool {
+    // `validate_args` already rejected the extendable-output functions
+    let HashAlgorithm::Fixed(algorithm) = args.algorithm else {
+        return false;
+    };
+
+    let mut succeeded = true;
+    for directory in directories {
+        match DirectoryManifest::build(algorithm, directory) {
+            Ok(manifest) if args.root_only => {
+                println!("{}  {}", manifest.root_digest(), directory.display())
+            }
+            Ok(manifest) => {
+                print!("{}", manifest);
+                println!("# {} root of {} ({} files): {}", algorithm, directory.display(), manifest.len(), manifest.root_digest());
+            }
+            Err(e) => {
+                report_error(directory, &e);
+                succeeded = false;
+            }
+        }
+    }
+    succeeded
+}
+
+fn length_extension(args: &LengthExtensionArgs) -> ExitCode {
+    let (message, extension) = (args.message.as_bytes(), args.extension.as_bytes());
+    let Some(secret) = &args.secret else {
+        // Attacker side: only the message, its tag and the secret length, or a bound on it, are known
+        let tag = match Digest::from_hex(args.algorithm, args.tag.as_deref().unwrap_or_default()) {
+            Ok(tag) => tag,
+            Err(e) => {
+                eprintln!("hash-functions: length-extension: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        let secret_lens = match (args.secret_len, args.max_secret_len) {
+            (Some(secret_len), _) => secret_len..=secret_len,
+            (None, Some(max_secret_len)) if max_secret_len > MAX_GUESSED_SECRET_LEN => {
+                eprintln!(
+                    "hash-functions: length-extension: --max-secret-len is at most {}",
+                    MAX_GUESSED_SECRET_LEN
+                );
+                return ExitCode::FAILURE;
+            }
+            (None, max_secret_len) => 0..=max_secret_len.unwrap_or_default(),
+        };
+        for secret_len in secret_lens {
+            match forge(&tag, secret_len, message, extension) {
+                Ok(forgery) => {
+                    // Each guess is a candidate for the verifier, the right one is accepted
+                    if args.max_secret_len.is_some() {
+                        println!("secret length: {}", secret_len);
+                    }
+                    println!("message: {}", hex::encode(&forgery.message));
+                    println!("tag: {}", forgery.tag);
+                }
+                Err(e) => {
+                    eprintln!("hash-functions: length-extension: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        return ExitCode::SUCCESS;
+    };
+
+    let secret = secret.as_bytes();
+    for algorithm in [Algorithm::Sha256, Algorithm::Sha512, Algorithm::Sha3_256, Algorithm::Keccak256] {
+        let tag = secret_prefix_mac(algorithm, secret, message);
+        let forgery = forge(&tag, secret.len(), message, extension).expect("supported algorithm");
+        let verdict = verify_secret_prefix_mac(algorithm, secret, &forgery.message, &forgery.tag);
+        println!("{}(secret || message): forged tag {}", algorithm, verdict_str(verdict.is_ok()));
+    }
+
+    // HMAC hashes the inner digest again with the key, the extended inner state is never the tag
+    let tag = Digest::new(Algorithm::Sha256, hmac(Algorithm::Sha256, secret, message)).expect("SHA-256 tag");
+    let forgery = forge(&tag, secret.len(), message, extension).expect("supported algorithm");
+    let verdict = verify_hmac(Algorithm::Sha256, secret, &forgery.message, forgery.tag.as_bytes());
+    println!("HMAC-{}: forged tag {}", Algorithm::Sha256, verdict_str(verdict.is_ok()));
+    println!("forged message: {}", hex::encode(&forgery.message));
+    ExitCode::SUCCESS
+}
+
+fn kmac_files(args: &KmacArgs) -> ExitCode {
+    let Ok(key) = hex::decode(&args.key) else {
+        eprintln!("hash-functions: kmac: invalid key: {}", Error::InvalidHexString);
+        return ExitCode::FAILURE;
+    };
+    let length = match xof_output_len(args.algorithm, args.length) {
+        Ok(length) => length,
+        Err(message) => {
+            eprintln!("hash-functions: kmac: {}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+    print_xof_outputs(&args.files, |mut input| {
+        let mut mac = Kmac::new(args.algorithm, &key, args.customization.as_bytes());
+        io::copy(&mut input, &mut mac)?;
+        Ok(match args.xof {
+            true => mac.finalize_xof(length),
+            false => mac.finalize(length),
+        })
+    })
+}
+
+fn cshake_files(args: &CshakeArgs) -> ExitCode {
+    let length = match xof_output_len(args.algorithm, args.length) {
+        Ok(length) => length,
+        Err(message) => {
+            eprintln!("hash-functions: cshake: {}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+    print_xof_outputs(&args.files, |mut input| {
+        let mut xof = Xof::cshake(args.algorithm, args.function_name.as_bytes(), args.customization.as_bytes());
+        io::copy(&mut input, &mut xof)?;
+        Ok(xof.finalize(length))
+    })
+}
+
+// Output length in bytes from a length in bits
+fn xof_output_len(algorithm: XofAlgorithm, bits: Option<usize>) -> Result<usize, String> {
+    match bits {
+        None => Ok(algorithm.default_output_len()),
+        Some(bits) if bits == 0 || bits % 8 != 0 => {
+            Err(format!("invalid length: {}, it must be a positive multiple of 8", bits))
+        }
+        Some(bits) => Ok(bits / 8),
+    }
+}
+
+// One `output  name` line per input, in the format of the unkeyed checksums
+fn print_xof_outputs<F>(files: &[PathBuf], output: F) -> ExitCode
+where
+    F: Fn(Box<dyn Read>) -> Result<Vec<u8>, Error>,
+{
+    let inputs = match files.is_empty() {
+        true => vec![PathBuf::from(STDIN_NAME)],
+        false => files.to_vec(),
+    };
+    let mut succeeded = true;
+    for input in &inputs {
+        match open_input(input).map_err(Error::from).and_then(&output) {
+            Ok(bytes) => println!("{}  {}", hex::encode(bytes), input.display()),
+            Err(e) => {
+                report_error(input, &e);
+                succeeded = false;
+            }
+        }
+    }
+    match succeeded {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+fn birthday(args: &BirthdayArgs) -> ExitCode {
+    let collision = CollisionSearch::new(args.algorithm, args.bits).and_then(|mut search| {
+        search.seed = args.seed;
+        search.distinguished_bits = args.distinguished_bits.unwrap_or(search.distinguished_bits);
+        search.max_table_entries = args.max_table_entries.unwrap_or(search.max_table_entries);
+        search.run()
+    });
+    let collision = match collision {
+        Ok(collision) => collision,
+        Err(e) => {
+            eprintln!("hash-functions: birthday: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let width = collision.bits.div_ceil(4) as usize;
+    println!("first: {}", hex::encode(&collision.first));
+    println!("second: {}", hex::encode(&collision.second));
+    println!("{} truncated to {} bits: {:0width$x}", collision.algorithm, collision.bits, collision.value);
+    println!(
+        "evaluations: {} (expected {:.0}, ratio {:.2})",
+        collision.evaluations,
+        collision.expected_evaluations,
+        collision.work_ratio()
+    );
+    println!("chains: {}, table entries: {}", collision.chains, collision.table_entries);
+    ExitCode::SUCCESS
+}
+
+fn verdict_str(accepted: bool) -> &'static str {
+    match accepted {
+        true => "ACCEPTED",
+        false => "rejected",
+    }
+}
+
+fn report_summary(manifest: &Path, summary: &CheckSummary, strict: bool) -> bool {
+    let checked = summary.verified + summary.failed + summary.missing;
+    if checked == 0 {
+        eprintln!(
+            "hash-functions: {}: no properly formatted checksum lines found",
+            manifest.display()
+        );
+        return false;
+    }
+    if summary.malformed > 0 {
+        eprintln!("hash-functions: WARNING: {} improperly formatted", plural(summary.malformed, "line is", "lines are"));
+    }
+    if summary.missing > 0 {
+        eprintln!("hash-functions: WARNING: {} not be read", plural(summary.missing, "listed file could", "listed files could"));
+    }
+    if summary.failed > 0 {
+        eprintln!("hash-functions: WARNING: {} NOT match", plural(summary.failed, "computed checksum did", "computed checksums did"));
+    }
+    summary.failed == 0 && summary.missing == 0 && !(strict && summary.malformed > 0)
+}
+
+fn plural(count: usize, singular: &str, plural: &str) -> String {
+    match count {
+        1 => format!("{} {}", count, singular),
+        _ => format!("{} {}", count, plural),
+    }
+}
+
+fn read_manifest(path: &Path) -> io::Result<String> {
+    match path == Path::new(STDIN_NAME) {
+        true => io::read_to_string(io::stdin().lock()),
+        false => fs::read_to_string(path),
+    }
+}
+
+fn checksum_line(args: &Args, path: &Path, format: ChecksumFormat) -> Result<String, Error> {
+    let name = path.to_string_lossy();
+    let mut input = open_input(path)?;
+    match args.algorithm {
+        HashAlgorithm::Fixed(algorithm) => {
+            let digest = digest_reader(algorithm, input)?;
+            Ok(format_line(&digest, &name, format, args.binary))
+        }
+        HashAlgorithm::Xof(algorithm) => {
+            let length = args.length.map(|bits| bits / 8).unwrap_or(algorithm.default_output_len());
+            let mut xof = Xof::shake(algorithm);
+            io::copy(&mut input, &mut xof)?;
+            Ok(format_xof_line(algorithm, &xof.finalize(length), &name, format, args.binary))
+        }
+    }
+}
+
+fn open_input(path: &Path) -> io::Result<Box<dyn Read>> {
+    match path == Path::new(STDIN_NAME) {
+        true => Ok(Box::new(io::stdin().lock())),
+        false => Ok(Box::new(File::open(path)?)),
+    }
+}
+
+// A directory is expanded to all the files below it, sorted so the output is stable between runs.
+// Anything else is returned as it is and the error, if any, shows up when it is opened
+fn list_files(path: &Path) -> Result<Vec<PathBuf>, Error> {
+    if path == Path::new(STDIN_NAME) || !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    // Links inside the tree are not followed, a link to a parent directory would loop forever.
+    // Links to files are hashed like the files
+    let mut files = Vec::new();
+    for entry in WalkDir::new(path).follow_links(false).sort_by_file_name() {
+        let entry = entry?;
+        let directory_link = entry.path_is_symlink() && entry.path().is_dir();
+        if !entry.file_type().is_dir() && !directory_link {
+            files.push(entry.into_path());
+        }
+    }
+    Ok(files)
+}
+
+fn report_error(path: &Path, error: &Error) {
+    eprintln!("hash-functions: {}: {}", path.display(), error);
 }
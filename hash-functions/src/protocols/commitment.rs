@@ -0,0 +1,162 @@
+use std::fmt;
+use std::str::FromStr;
+
+use subtle::ConstantTimeEq;
+
+use crate::algorithms::hasher::Hasher;
+use crate::elements::{algorithm::Algorithm, digest::Digest, encoding::Encoding, multihash::Multihash};
+use crate::utils::error::Error;
+
+// 256 random bits: the value stays hidden even when it has only a few possible values
+// (a bid amount, the side of a coin)
+pub const NONCE_LEN: usize = 32;
+
+// Binding and hiding commitment H(domain length || domain || nonce || value).
+// The domain separates the uses of the scheme: a commitment made for an auction cannot be opened
+// as a coin flip. The holder publishes the commitment, keeps the `Opening` and reveals it later
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Commitment {
+    domain: String,
+    digest: Digest,
+}
+
+// The secret part: the value and the nonce that hides it
+#[derive(Clone, PartialEq, Eq)]
+pub struct Opening {
+    value: Vec<u8>,
+    nonce: [u8; NONCE_LEN],
+}
+
+impl Commitment {
+    // Commits with a nonce from the operating system generator
+    pub fn commit(algorithm: Algorithm, domain: &str, value: &[u8]) -> Result<(Commitment, Opening), Error> {
+        let mut nonce = [0u8; NONCE_LEN];
+        getrandom::getrandom(&mut nonce)?;
+        Commitment::commit_with_nonce(algorithm, domain, value, nonce)
+    }
+
+    // The nonce must be random and used once, a guessable nonce reveals small values
+    pub fn commit_with_nonce(
+        algorithm: Algorithm,
+        domain: &str,
+        value: &[u8],
+        nonce: [u8; NONCE_LEN],
+    ) -> Result<(Commitment, Opening), Error> {
+        validate_domain(domain)?;
+        let opening = Opening { value: value.to_vec(), nonce };
+        let commitment = Commitment {
+            domain: domain.to_string(),
+            digest: commitment_digest(algorithm, domain, &opening),
+        };
+        Ok((commitment, opening))
+    }
+
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.digest.algorithm()
+    }
+
+    pub fn digest(&self) -> &Digest {
+        &self.digest
+    }
+
+    // Checks a revealed opening, the comparison takes the same time whatever the digest
+    pub fn verify(&self, opening: &Opening) -> Result<(), Error> {
+        let expected = commitment_digest(self.algorithm(), &self.domain, opening);
+        match bool::from(expected.as_bytes().ct_eq(self.digest.as_bytes())) {
+            true => Ok(()),
+            false => Err(Error::DigestMismatch),
+        }
+    }
+
+    // Verifies and returns the committed value
+    pub fn open<'a>(&self, opening: &'a Opening) -> Result<&'a [u8], Error> {
+        self.verify(opening)?;
+        Ok(opening.value())
+    }
+}
+
+impl Opening {
+    pub fn new(value: Vec<u8>, nonce: [u8; NONCE_LEN]) -> Opening {
+        Opening { value, nonce }
+    }
+
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+
+    pub fn nonce(&self) -> &[u8; NONCE_LEN] {
+        &self.nonce
+    }
+}
+
+// `<domain>:<multihash in hex>`, the multihash carries the hash function
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.domain, Multihash::from(&self.digest).to_hex())
+    }
+}
+
+impl FromStr for Commitment {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (domain, multihash) = s
+            .rsplit_once(':')
+            .ok_or_else(|| Error::InvalidParameter("commitment without domain".to_string()))?;
+        validate_domain(domain)?;
+        Ok(Commitment {
+            domain: domain.to_string(),
+            digest: Multihash::from_hex(multihash)?.to_digest()?,
+        })
+    }
+}
+
+// `<nonce in hex>:<value in base64url>`, what the holder sends when revealing
+impl fmt::Display for Opening {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", Encoding::Hex.encode(&self.nonce), Encoding::Base64Url.encode(&self.value))
+    }
+}
+
+impl FromStr for Opening {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (nonce, value) = s
+            .split_once(':')
+            .ok_or_else(|| Error::InvalidParameter("opening without nonce".to_string()))?;
+        Ok(Opening {
+            value: Encoding::Base64Url.decode(value)?,
+            nonce: Encoding::Hex.decode_array::<NONCE_LEN>(nonce)?,
+        })
+    }
+}
+
+// The nonce is as secret as the value until the reveal, it is not printed
+impl fmt::Debug for Opening {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Opening").field("value_len", &self.value.len()).finish_non_exhaustive()
+    }
+}
+
+// The nonce has a fixed length and the value comes last, only the domain needs its length
+fn commitment_digest(algorithm: Algorithm, domain: &str, opening: &Opening) -> Digest {
+    let mut hasher = Hasher::new(algorithm);
+    hasher.update(&(domain.len() as u64).to_be_bytes());
+    hasher.update(domain.as_bytes());
+    hasher.update(&opening.nonce);
+    hasher.update(&opening.value);
+    hasher.finalize()
+}
+
+// Printable ASCII without `:`, so the text format can be split without ambiguity
+fn validate_domain(domain: &str) -> Result<(), Error> {
+    match !domain.is_empty() && domain.bytes().all(|b| b.is_ascii_graphic() && b != b':') {
+        true => Ok(()),
+        false => Err(Error::InvalidParameter(format!("invalid commitment domain {:?}", domain))),
+    }
+}
@@ -0,0 +1,2 @@
+pub mod commitment;
+pub mod proof_of_work;
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::algorithms::hasher::Hasher;
+use crate::elements::{algorithm::Algorithm, encoding::Encoding};
+use crate::utils::error::Error;
+
+// Version of the hashcash format, the only one in use since 2003
+const VERSION: &str = "1";
+
+// Random bytes of a stamp, 16 characters once encoded in base64
+const RAND_LEN: usize = 12;
+
+// More than this and minting takes days on a laptop
+pub const MAX_BITS: u32 = 40;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+// Hashcash v1 stamp: `1:bits:date:resource:extension:rand:counter`.
+// The date is `YYMMDDhhmmss` in UTC (`YYMMDD` and `YYMMDDhhmm` are accepted when parsing).
+// The stamp is worth `bits` when the digest of its text starts with that many zero bits,
+// about 2^bits hashes to find and a single one to check
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stamp {
+    pub bits: u32,
+    pub date: String,
+    pub resource: String,
+    pub extension: String,
+    pub rand: String,
+    pub counter: String,
+}
+
+// What the receiving side accepts. The original hashcash uses SHA-1, the algorithm here is a
+// parameter that both sides must agree on, it is not part of the stamp
+#[derive(Clone, Debug)]
+pub struct StampPolicy {
+    pub algorithm: Algorithm,
+    pub bits: u32,
+    // Older stamps are refused, so the spent store only has to remember this long
+    pub max_age: u64,
+    // Tolerance for the clocks of the clients
+    pub max_clock_skew: u64,
+}
+
+// Remembers the stamps already accepted, so a stamp pays for a single use.
+// Implement it on a database table to share it between servers
+pub trait SpentStore {
+    // Records the stamp, `false` if it was already spent
+    fn spend(&mut self, stamp: &str, timestamp: u64) -> Result<bool, Error>;
+}
+
+// Spent stamps of this process with their date, for a single server
+#[derive(Clone, Debug, Default)]
+pub struct MemorySpentStore {
+    spent: HashMap<String, u64>,
+}
+
+impl Stamp {
+    // Searches the counter giving `bits` leading zero bits, dated now
+    pub fn mint(algorithm: Algorithm, resource: &str, bits: u32) -> Result<Stamp, Error> {
+        Stamp::mint_at(algorithm, resource, bits, now())
+    }
+
+    pub fn mint_at(algorithm: Algorithm, resource: &str, bits: u32, timestamp: u64) -> Result<Stamp, Error> {
+        if bits > MAX_BITS.min(8 * algorithm.output_len() as u32) {
+            return Err(Error::InvalidParameter(format!("at most {} bits of work", MAX_BITS)));
+        }
+        validate_field("resource", resource)?;
+        let mut rand = [0u8; RAND_LEN];
+        getrandom::getrandom(&mut rand)?;
+
+        let mut stamp = Stamp {
+            bits,
+            date: format_date(timestamp),
+            resource: resource.to_string(),
+            extension: String::new(),
+            rand: Encoding::Base64.encode(&rand),
+            counter: String::new(),
+        };
+
+        // Everything but the counter is hashed once, each attempt clones the hasher
+        let mut prefix = Hasher::new(algorithm);
+        prefix.update(stamp.to_string().as_bytes());
+        for counter in 0u64.. {
+            let counter_text = encode_counter(counter);
+            let mut hasher = prefix.clone();
+            hasher.update(counter_text.as_bytes());
+            if leading_zero_bits(hasher.finalize().as_bytes()) >= bits {
+                stamp.counter = counter_text;
+                return Ok(stamp);
+            }
+        }
+        unreachable!("2^64 attempts")
+    }
+
+    // Leading zero bits of the digest of the stamp, the work it holds
+    pub fn value(&self, algorithm: Algorithm) -> u32 {
+        let mut hasher = Hasher::new(algorithm);
+        hasher.update(self.to_string().as_bytes());
+        leading_zero_bits(hasher.finalize().as_bytes())
+    }
+
+    // Seconds since the Unix epoch of the date field
+    pub fn timestamp(&self) -> Result<u64, Error> {
+        parse_date(&self.date)
+    }
+}
+
+impl StampPolicy {
+    // Stamps of the last 48 hours, clocks up to 1 hour ahead
+    pub fn new(algorithm: Algorithm, bits: u32) -> StampPolicy {
+        StampPolicy {
+            algorithm,
+            bits,
+            max_age: 2 * SECONDS_PER_DAY,
+            max_clock_skew: 3600,
+        }
+    }
+
+    pub fn verify<S: SpentStore>(&self, stamp: &str, resource: &str, spent: &mut S) -> Result<Stamp, Error> {
+        self.verify_at(stamp, resource, spent, now())
+    }
+
+    // The cheap checks come first and the spent store is only updated by valid stamps,
+    // so invalid stamps cost a single hash and cannot fill the store
+    pub fn verify_at<S: SpentStore>(
+        &self,
+        stamp: &str,
+        resource: &str,
+        spent: &mut S,
+        now: u64,
+    ) -> Result<Stamp, Error> {
+        let parsed: Stamp = stamp.parse()?;
+        if parsed.resource != resource {
+            return Err(Error::InvalidStamp(format!("issued for {}", parsed.resource)));
+        }
+        if parsed.bits < self.bits {
+            return Err(Error::InvalidStamp(format!("{} bits claimed, {} required", parsed.bits, self.bits)));
+        }
+
+        let timestamp = parsed.timestamp()?;
+        if timestamp > now + self.max_clock_skew {
+            return Err(Error::InvalidStamp("dated in the future".to_string()));
+        }
+        if timestamp + self.max_age < now {
+            return Err(Error::InvalidStamp("expired".to_string()));
+        }
+
+        let value = parsed.value(self.algorithm);
+        if value < parsed.bits {
+            return Err(Error::InvalidStamp(format!("{} bits of work, {} claimed", value, parsed.bits)));
+        }
+
+        match spent.spend(stamp, timestamp)? {
+            true => Ok(parsed),
+            false => Err(Error::StampAlreadySpent),
+        }
+    }
+}
+
+impl MemorySpentStore {
+    pub fn new() -> MemorySpentStore {
+        MemorySpentStore::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.spent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spent.is_empty()
+    }
+
+    // Forgets the stamps dated before `timestamp`, the policy rejects them as expired anyway
+    pub fn purge_before(&mut self, timestamp: u64) {
+        self.spent.retain(|_, date| *date >= timestamp);
+    }
+}
+
+impl SpentStore for MemorySpentStore {
+    fn spend(&mut self, stamp: &str, timestamp: u64) -> Result<bool, Error> {
+        Ok(self.spent.insert(stamp.to_string(), timestamp).is_none())
+    }
+}
+
+impl fmt::Display for Stamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}:{}:{}:{}",
+            VERSION, self.bits, self.date, self.resource, self.extension, self.rand, self.counter
+        )
+    }
+}
+
+// Only the canonical text is accepted, the parsed stamp prints back to the same bytes that were hashed
+impl FromStr for Stamp {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split(':').collect();
+        let [version, bits, date, resource, extension, rand, counter] = fields[..] else {
+            return Err(Error::InvalidStamp("expected 7 fields".to_string()));
+        };
+        if version != VERSION {
+            return Err(Error::InvalidStamp(format!("unsupported version {}", version)));
+        }
+        let bits = bits
+            .parse::<u32>()
+            .ok()
+            .filter(|value| value.to_string() == bits)
+            .ok_or_else(|| Error::InvalidStamp(format!("invalid bits {}", bits)))?;
+        parse_date(date)?;
+
+        Ok(Stamp {
+            bits,
+            date: date.to_string(),
+            resource: resource.to_string(),
+            extension: extension.to_string(),
+            rand: rand.to_string(),
+            counter: counter.to_string(),
+        })
+    }
+}
+
+fn leading_zero_bits(bytes: &[u8]) -> u32 {
+    let mut bits = 0;
+    for byte in bytes {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+// Base64 of the big-endian counter without its leading zero bytes, as the hashcash tools do
+fn encode_counter(counter: u64) -> String {
+    let bytes = counter.to_be_bytes();
+    let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(7);
+    Encoding::Base64Unpadded.encode(&bytes[start..])
+}
+
+// `:` would shift the fields
+fn validate_field(name: &str, value: &str) -> Result<(), Error> {
+    match value.contains(':') || value.contains(char::is_whitespace) {
+        true => Err(Error::InvalidParameter(format!("{} must not contain ':' or spaces", name))),
+        false => Ok(()),
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+// YYMMDDhhmmss in UTC, years 2000 to 2099
+fn format_date(timestamp: u64) -> String {
+    let (days, seconds) = (timestamp / SECONDS_PER_DAY, timestamp % SECONDS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:02}{:02}{:02}{:02}{:02}{:02}",
+        year % 100,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn parse_date(date: &str) -> Result<u64, Error> {
+    let invalid = || Error::InvalidStamp(format!("invalid date {}", date));
+    if !matches!(date.len(), 6 | 10 | 12) || !date.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let field = |index: usize| date.get(2 * index..2 * index + 2).map_or(0, |two| two.parse::<u64>().unwrap());
+    let (year, month, day) = (2000 + field(0), field(1), field(2));
+    let (hour, minute, second) = (field(3), field(4), field(5));
+
+    let days_in_month = match month {
+        2 if year % 4 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    if !(1..=12).contains(&month) || !(1..=days_in_month).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return Err(invalid());
+    }
+    Ok(days_from_civil(year, month, day) * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second)
+}
+
+// Days since 1970-01-01 to the proleptic Gregorian calendar and back,
+// from the date algorithms of Howard Hinnant
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted_month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
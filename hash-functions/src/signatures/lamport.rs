@@ -0,0 +1,153 @@
+use std::fmt;
+
+use crate::signatures::primitives::{check_length, hash_values, Address, HashFamily, HashValue, N};
+use crate::utils::error::Error;
+
+// Bits of the message digest, each one is signed by revealing one secret of a pair
+const BITS: usize = 8 * N;
+
+// Lamport one-time signature (1979): 2 x 256 secrets, their hashes are the public key.
+// Signing reveals one secret per bit of the digest of the message. A second signature reveals
+// other secrets and lets anyone forge signatures, so the key refuses to sign twice.
+// The secrets are derived from a 32-byte seed, the seed is all there is to keep
+pub struct LamportSigningKey {
+    family: HashFamily,
+    seed: HashValue,
+    used: bool,
+}
+
+// 16 KiB: the hashes of the 512 secrets, pair by pair
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LamportPublicKey {
+    family: HashFamily,
+    hashes: Vec<HashValue>,
+}
+
+// 8 KiB: the secret of every bit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LamportSignature {
+    secrets: Vec<HashValue>,
+}
+
+impl LamportSigningKey {
+    pub fn from_seed(family: HashFamily, seed: HashValue) -> LamportSigningKey {
+        LamportSigningKey { family, seed, used: false }
+    }
+
+    // Key with a seed from the operating system generator
+    pub fn generate(family: HashFamily) -> Result<LamportSigningKey, Error> {
+        let mut seed = [0u8; N];
+        getrandom::getrandom(&mut seed)?;
+        Ok(LamportSigningKey::from_seed(family, seed))
+    }
+
+    pub fn family(&self) -> HashFamily {
+        self.family
+    }
+
+    pub fn is_used(&self) -> bool {
+        self.used
+    }
+
+    pub fn public_key(&self) -> LamportPublicKey {
+        let hashes = (0..2 * BITS)
+            .map(|index| self.family.hash(&self.secret(index / BITS, index % BITS)))
+            .collect();
+        LamportPublicKey { family: self.family, hashes }
+    }
+
+    // Marks the key as used before returning the signature. Save the key with `to_bytes`
+    // after signing, a copy saved before would sign again
+    pub fn sign(&mut self, message: &[u8]) -> Result<LamportSignature, Error> {
+        if self.used {
+            return Err(Error::OneTimeKeyReused);
+        }
+        self.used = true;
+
+        let digest = self.family.hash(message);
+        let secrets = (0..BITS).map(|bit| self.secret(bit_value(&digest, bit), bit)).collect();
+        Ok(LamportSignature { secrets })
+    }
+
+    // family || used || seed
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&[self.family.id(), u8::from(self.used)][..], &self.seed].concat()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<LamportSigningKey, Error> {
+        check_length(bytes, 2 + N)?;
+        Ok(LamportSigningKey {
+            family: HashFamily::from_id(bytes[0])?,
+            used: bytes[1] != 0,
+            seed: bytes[2..].try_into().expect("length checked"),
+        })
+    }
+
+    // Secret revealed when the bit `bit` of the digest is `value`
+    fn secret(&self, value: usize, bit: usize) -> HashValue {
+        let mut address = Address::default();
+        address.set_chain(bit as u32);
+        address.set_hash(value as u32);
+        self.family.prf(&self.seed, &address)
+    }
+}
+
+impl LamportPublicKey {
+    pub fn family(&self) -> HashFamily {
+        self.family
+    }
+
+    pub fn verify(&self, message: &[u8], signature: &LamportSignature) -> Result<(), Error> {
+        let digest = self.family.hash(message);
+        let valid = signature
+            .secrets
+            .iter()
+            .enumerate()
+            .all(|(bit, secret)| self.family.hash(secret) == self.hashes[bit_value(&digest, bit) * BITS + bit]);
+        match valid {
+            true => Ok(()),
+            false => Err(Error::InvalidSignature),
+        }
+    }
+
+    // family || hashes of the secrets for the zero bits || hashes for the one bits
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.family.id()];
+        self.hashes.iter().for_each(|hash| bytes.extend_from_slice(hash));
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<LamportPublicKey, Error> {
+        check_length(bytes, 1 + 2 * BITS * N)?;
+        Ok(LamportPublicKey {
+            family: HashFamily::from_id(bytes[0])?,
+            hashes: hash_values(&bytes[1..]),
+        })
+    }
+}
+
+impl LamportSignature {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.secrets.concat()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<LamportSignature, Error> {
+        check_length(bytes, BITS * N)?;
+        Ok(LamportSignature { secrets: hash_values(bytes) })
+    }
+}
+
+// The seed is the whole secret, it is not printed
+impl fmt::Debug for LamportSigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LamportSigningKey")
+            .field("family", &self.family)
+            .field("used", &self.used)
+            .finish_non_exhaustive()
+    }
+}
+
+// Most significant bit first
+fn bit_value(digest: &HashValue, bit: usize) -> usize {
+    usize::from(digest[bit / 8] >> (7 - bit % 8) & 1)
+}
@@ -0,0 +1,4 @@
+pub mod lamport;
+pub mod mss;
+pub mod primitives;
+pub mod wots;
@@ -0,0 +1,473 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+
+use crate::signatures::primitives::{check_length, hash_values, Address, HashFamily, HashValue, N};
+use crate::signatures::wots::{public_key_from_signature, WotsSignature, WotsSigningKey, LEN};
+use crate::utils::error::Error;
+
+// 2^20 signatures per key. The whole tree is computed every time a key is created or loaded: about
+// 3 * 10^9 hash calls at this height, over half an hour on one core against a few seconds at height 10
+pub const MAX_HEIGHT: u32 = 20;
+
+// Merkle signature scheme following XMSS (RFC 8391 section 4.1): 2^height WOTS+ keys whose public keys,
+// compressed by an L-tree, are the leaves of a binary hash tree. The root is the public key.
+// Signature `i` is made with the one-time key of leaf `i`, the authentication path lets the verifier
+// climb from that leaf to the root.
+//
+// The scheme is stateful: a leaf index used twice breaks the security of its one-time key.
+// `sign` moves the index forward before returning the signature, `MssKeyFile` saves it to disk
+// before the signature leaves the process
+pub struct MssSigningKey {
+    family: HashFamily,
+    height: u32,
+    next_index: u32,
+    secret_seed: HashValue,
+    prf_key: HashValue,
+    public_seed: HashValue,
+    // Nodes of the tree level by level, the leaves first and the root last
+    levels: Vec<Vec<HashValue>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MssPublicKey {
+    family: HashFamily,
+    height: u32,
+    root: HashValue,
+    public_seed: HashValue,
+}
+
+// 2.5 KiB for a tree of height 10: index, randomness, one-time signature and authentication path
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MssSignature {
+    index: u32,
+    randomness: HashValue,
+    wots: WotsSignature,
+    auth_path: Vec<HashValue>,
+}
+
+// Signing key stored in a file. Each signature writes the next index to a temporary file,
+// syncs it, renames it over the key and syncs the directory before the signature is returned:
+// after a crash the index on disk is never behind a signature that was handed out.
+//
+// A `<key>.lock` file next to the key is held while the key is open, so two processes never sign
+// with the same leaf. A lock left by a crashed process has to be removed by hand
+pub struct MssKeyFile {
+    path: PathBuf,
+    key: MssSigningKey,
+    _lock: KeyLock,
+}
+
+// Removes the lock file when the key file is closed
+struct KeyLock {
+    path: PathBuf,
+}
+
+impl MssSigningKey {
+    // The three secrets of the key are derived from `seed`
+    pub fn from_seed(family: HashFamily, height: u32, seed: HashValue) -> Result<MssSigningKey, Error> {
+        let derive = |label: &[u8]| family.hash(&[label, &seed].concat());
+        MssSigningKey::from_parts(
+            family,
+            height,
+            0,
+            derive(b"MSS secret seed"),
+            derive(b"MSS PRF key"),
+            derive(b"MSS public seed"),
+        )
+    }
+
+    pub fn generate(family: HashFamily, height: u32) -> Result<MssSigningKey, Error> {
+        let mut seed = [0u8; N];
+        getrandom::getrandom(&mut seed)?;
+        MssSigningKey::from_seed(family, height, seed)
+    }
+
+    pub fn family(&self) -> HashFamily {
+        self.family
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // Index of the leaf of the next signature
+    pub fn next_index(&self) -> u32 {
+        self.next_index
+    }
+
+    // Signatures left before the key is exhausted
+    pub fn remaining(&self) -> u64 {
+        (1u64 << self.height) - u64::from(self.next_index)
+    }
+
+    pub fn public_key(&self) -> MssPublicKey {
+        MssPublicKey {
+            family: self.family,
+            height: self.height,
+            root: self.root(),
+            public_seed: self.public_seed,
+        }
+    }
+
+    pub fn sign(&mut self, message: &[u8]) -> Result<MssSignature, Error> {
+        if self.remaining() == 0 {
+            return Err(Error::SigningKeyExhausted);
+        }
+        let index = self.next_index;
+        self.next_index += 1;
+        Ok(self.sign_at(index, message))
+    }
+
+    // family || height || next index (u32 BE) || secret seed || PRF key || public seed || root.
+    // The root lets `from_bytes` detect a damaged key
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            &[self.family.id(), self.height as u8][..],
+            &self.next_index.to_be_bytes(),
+            &self.secret_seed,
+            &self.prf_key,
+            &self.public_seed,
+            &self.root(),
+        ]
+        .concat()
+    }
+
+    // Computes the whole tree again
+    pub fn from_bytes(bytes: &[u8]) -> Result<MssSigningKey, Error> {
+        check_length(bytes, 6 + 4 * N)?;
+        let values = hash_values(&bytes[6..]);
+        let key = MssSigningKey::from_parts(
+            HashFamily::from_id(bytes[0])?,
+            u32::from(bytes[1]),
+            u32::from_be_bytes(bytes[2..6].try_into().expect("length checked")),
+            values[0],
+            values[1],
+            values[2],
+        )?;
+        match key.root() == values[3] {
+            true => Ok(key),
+            false => Err(Error::DigestMismatch),
+        }
+    }
+
+    fn from_parts(
+        family: HashFamily,
+        height: u32,
+        next_index: u32,
+        secret_seed: HashValue,
+        prf_key: HashValue,
+        public_seed: HashValue,
+    ) -> Result<MssSigningKey, Error> {
+        if !(1..=MAX_HEIGHT).contains(&height) {
+            return Err(Error::InvalidParameter(format!("tree height must be between 1 and {}", MAX_HEIGHT)));
+        }
+        if u64::from(next_index) > 1u64 << height {
+            return Err(Error::InvalidParameter(format!("leaf index {} beyond the tree", next_index)));
+        }
+
+        let mut key = MssSigningKey {
+            family,
+            height,
+            next_index,
+            secret_seed,
+            prf_key,
+            public_seed,
+            levels: Vec::new(),
+        };
+        let leaves: Vec<HashValue> = (0..1u32 << height).into_par_iter().map(|index| key.leaf(index)).collect();
+        key.levels = build_levels(family, &public_seed, leaves);
+        Ok(key)
+    }
+
+    fn root(&self) -> HashValue {
+        self.levels[self.height as usize][0]
+    }
+
+    fn wots_key(&self, index: u32) -> WotsSigningKey {
+        WotsSigningKey::new(self.family, self.secret_seed, self.public_seed, ots_address(index))
+    }
+
+    // L-tree of the WOTS+ public key of the leaf
+    fn leaf(&self, index: u32) -> HashValue {
+        let chains = self.wots_key(index).public_key().chains().to_vec();
+        ltree(self.family, &self.public_seed, index, chains)
+    }
+
+    fn sign_at(&self, index: u32, message: &[u8]) -> MssSignature {
+        // PRF(SK_PRF, toByte(index, 32)), the 32 bytes have the layout of an address
+        let mut index_bytes = [0u8; N];
+        index_bytes[N - 4..].copy_from_slice(&index.to_be_bytes());
+        let randomness = self.family.prf(&self.prf_key, &Address::from_bytes(&index_bytes));
+        let digest = message_digest(self.family, &randomness, &self.root(), index, message);
+
+        let wots = self.wots_key(index).sign_digest(&digest).expect("fresh one-time key");
+        let auth_path = (0..self.height as usize)
+            .map(|level| self.levels[level][(index >> level) as usize ^ 1])
+            .collect();
+        MssSignature { index, randomness, wots, auth_path }
+    }
+}
+
+impl MssPublicKey {
+    pub fn family(&self) -> HashFamily {
+        self.family
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn root(&self) -> &HashValue {
+        &self.root
+    }
+
+    // Computes the root from the signature and compares it with the key
+    pub fn verify(&self, message: &[u8], signature: &MssSignature) -> Result<(), Error> {
+        if u64::from(signature.index) >= 1u64 << self.height || signature.auth_path.len() != self.height as usize {
+            return Err(Error::InvalidSignature);
+        }
+
+        let index = signature.index;
+        let digest = message_digest(self.family, &signature.randomness, &self.root, index, message);
+        let chains = public_key_from_signature(self.family, &self.public_seed, ots_address(index), &digest, &signature.wots);
+        let mut node = ltree(self.family, &self.public_seed, index, chains);
+
+        let mut address = Address::default();
+        address.set_type(Address::TYPE_HASH_TREE);
+        for (level, sibling) in signature.auth_path.iter().enumerate() {
+            address.set_tree_height(level as u32);
+            address.set_tree_index(index >> (level + 1));
+            node = match (index >> level) & 1 {
+                0 => rand_hash(self.family, &self.public_seed, &mut address, &node, sibling),
+                _ => rand_hash(self.family, &self.public_seed, &mut address, sibling, &node),
+            };
+        }
+
+        match node == self.root {
+            true => Ok(()),
+            false => Err(Error::InvalidSignature),
+        }
+    }
+
+    // family || height || root || public seed
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&[self.family.id(), self.height as u8][..], &self.root, &self.public_seed].concat()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<MssPublicKey, Error> {
+        check_length(bytes, 2 + 2 * N)?;
+        let height = u32::from(bytes[1]);
+        if !(1..=MAX_HEIGHT).contains(&height) {
+            return Err(Error::InvalidParameter(format!("tree height must be between 1 and {}", MAX_HEIGHT)));
+        }
+        Ok(MssPublicKey {
+            family: HashFamily::from_id(bytes[0])?,
+            height,
+            root: bytes[2..2 + N].try_into().expect("length checked"),
+            public_seed: bytes[2 + N..].try_into().expect("length checked"),
+        })
+    }
+}
+
+impl MssSignature {
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn auth_path(&self) -> &[HashValue] {
+        &self.auth_path
+    }
+
+    // index (u32 BE) || randomness || WOTS+ signature || authentication path, as in RFC 8391
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = [&self.index.to_be_bytes()[..], &self.randomness, &self.wots.to_bytes()].concat();
+        self.auth_path.iter().for_each(|node| bytes.extend_from_slice(node));
+        bytes
+    }
+
+    // The length of the authentication path depends on the height of the tree
+    pub fn from_bytes(height: u32, bytes: &[u8]) -> Result<MssSignature, Error> {
+        check_length(bytes, 4 + N + LEN * N + height as usize * N)?;
+        let wots_end = 4 + N + LEN * N;
+        Ok(MssSignature {
+            index: u32::from_be_bytes(bytes[..4].try_into().expect("length checked")),
+            randomness: bytes[4..4 + N].try_into().expect("length checked"),
+            wots: WotsSignature::from_bytes(&bytes[4 + N..wots_end])?,
+            auth_path: hash_values(&bytes[wots_end..]),
+        })
+    }
+}
+
+impl MssKeyFile {
+    // Writes a new key, an existing file is never overwritten
+    pub fn create<P: AsRef<Path>>(path: P, key: MssSigningKey) -> Result<MssKeyFile, Error> {
+        let path = path.as_ref().to_path_buf();
+        let lock = KeyLock::acquire(&path)?;
+        let mut file = File::options().write(true).create_new(true).open(&path)?;
+        file.write_all(&key.to_bytes())?;
+        file.sync_all()?;
+        sync_parent(&path)?;
+        Ok(MssKeyFile { path, key, _lock: lock })
+    }
+
+    // Fails with `KeyFileInUse` while another `MssKeyFile` has the key open
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<MssKeyFile, Error> {
+        let path = path.as_ref().to_path_buf();
+        let lock = KeyLock::acquire(&path)?;
+        let key = MssSigningKey::from_bytes(&fs::read(&path)?)?;
+        Ok(MssKeyFile { path, key, _lock: lock })
+    }
+
+    pub fn key(&self) -> &MssSigningKey {
+        &self.key
+    }
+
+    // The index is saved first. If saving fails, the leaf is skipped and no signature is returned
+    pub fn sign(&mut self, message: &[u8]) -> Result<MssSignature, Error> {
+        if self.key.remaining() == 0 {
+            return Err(Error::SigningKeyExhausted);
+        }
+        let index = self.key.next_index;
+        self.key.next_index += 1;
+        self.save()?;
+        Ok(self.key.sign_at(index, message))
+    }
+
+    fn save(&self) -> Result<(), Error> {
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&self.key.to_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+        // The rename is only durable once the directory entry is
+        sync_parent(&self.path)
+    }
+}
+
+impl KeyLock {
+    fn acquire(key_path: &Path) -> Result<KeyLock, Error> {
+        let mut path = key_path.to_path_buf().into_os_string();
+        path.push(".lock");
+        let path = PathBuf::from(path);
+        match File::options().write(true).create_new(true).open(&path) {
+            Ok(_) => Ok(KeyLock { path }),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                Err(Error::KeyFileInUse(key_path.display().to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl Drop for KeyLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn sync_parent(path: &Path) -> Result<(), Error> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(parent)?.sync_all()?;
+    Ok(())
+}
+
+// The seeds are secret, they are not printed
+impl fmt::Debug for MssSigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MssSigningKey")
+            .field("family", &self.family)
+            .field("height", &self.height)
+            .field("next_index", &self.next_index)
+            .finish_non_exhaustive()
+    }
+}
+
+fn ots_address(index: u32) -> Address {
+    let mut address = Address::default();
+    address.set_type(Address::TYPE_OTS);
+    address.set_leaf(index);
+    address
+}
+
+// H_msg(r || root || toByte(index, 32), message): the randomness keeps the signed digest
+// unpredictable, a collision found in advance is useless
+fn message_digest(family: HashFamily, randomness: &HashValue, root: &HashValue, index: u32, message: &[u8]) -> HashValue {
+    let mut index_bytes = [0u8; N];
+    index_bytes[N - 4..].copy_from_slice(&index.to_be_bytes());
+    family.h_msg(&[&randomness[..], root, &index_bytes].concat(), message)
+}
+
+// RAND_HASH of RFC 8391: H with a key and two bitmasks from the public seed and the address
+fn rand_hash(
+    family: HashFamily,
+    public_seed: &HashValue,
+    address: &mut Address,
+    left: &HashValue,
+    right: &HashValue,
+) -> HashValue {
+    let mut masked = [*left, *right];
+    address.set_key_and_mask(0);
+    let key = family.prf(public_seed, address);
+    for (mask_index, value) in masked.iter_mut().enumerate() {
+        address.set_key_and_mask(mask_index as u32 + 1);
+        let mask = family.prf(public_seed, address);
+        value.iter_mut().zip(mask).for_each(|(byte, mask_byte)| *byte ^= mask_byte);
+    }
+    address.set_key_and_mask(0);
+    family.h(&key, &masked[0], &masked[1])
+}
+
+// Unbalanced tree over the 67 chains of a WOTS+ public key, an odd node is lifted to the next level
+fn ltree(family: HashFamily, public_seed: &HashValue, index: u32, chains: Vec<HashValue>) -> HashValue {
+    let mut address = Address::default();
+    address.set_type(Address::TYPE_LTREE);
+    address.set_leaf(index);
+
+    let mut nodes = chains;
+    let mut height = 0;
+    while nodes.len() > 1 {
+        address.set_tree_height(height);
+        let mut next = Vec::with_capacity(nodes.len().div_ceil(2));
+        for (pair_index, pair) in nodes.chunks(2).enumerate() {
+            match pair {
+                [left, right] => {
+                    address.set_tree_index(pair_index as u32);
+                    next.push(rand_hash(family, public_seed, &mut address, left, right));
+                }
+                [single] => next.push(*single),
+                _ => unreachable!("chunks of two"),
+            }
+        }
+        nodes = next;
+        height += 1;
+    }
+    nodes[0]
+}
+
+fn build_levels(family: HashFamily, public_seed: &HashValue, leaves: Vec<HashValue>) -> Vec<Vec<HashValue>> {
+    let mut levels = vec![leaves];
+    while levels.last().expect("leaves").len() > 1 {
+        let level = levels.len() - 1;
+        let next = levels[level]
+            .par_chunks(2)
+            .enumerate()
+            .map(|(index, pair)| {
+                let mut address = Address::default();
+                address.set_type(Address::TYPE_HASH_TREE);
+                address.set_tree_height(level as u32);
+                address.set_tree_index(index as u32);
+                rand_hash(family, public_seed, &mut address, &pair[0], &pair[1])
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
@@ -0,0 +1,224 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::algorithms::hasher::Hasher;
+use crate::algorithms::xof_algo::Xof;
+use crate::elements::algorithm::{Algorithm, XofAlgorithm};
+use crate::utils::error::Error;
+
+// Bytes of the hash values, keys and seeds: 256 bits, 128 bits of security against a quantum attacker
+pub const N: usize = 32;
+
+pub type HashValue = [u8; N];
+
+// Hash functions of the XMSS parameter sets with n = 32 (RFC 8391 section 5.1 and SP 800-208).
+// Every function is the hash of `toByte(padding, 32) || key || message`, the padding
+// separates the functions from each other
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HashFamily {
+    // SHA2-256
+    Sha256,
+    // SHAKE256 with a 256-bit output
+    Shake256,
+}
+
+// Paddings of RFC 8391 and SP 800-208
+const PADDING_F: u8 = 0;
+const PADDING_H: u8 = 1;
+const PADDING_H_MSG: u8 = 2;
+const PADDING_PRF: u8 = 3;
+const PADDING_PRF_KEYGEN: u8 = 4;
+
+// 32-byte ADRS of RFC 8391 section 2.5: the position of every hash call in the structure,
+// so each call is a different function and a collision in one does not help with another.
+//
+//     word 0      layer
+//     words 1-2   tree
+//     word 3      type: 0 one-time signature, 1 L-tree, 2 hash tree
+//     words 4-6   OTS, chain, hash  |  L-tree, height, index  |  padding, height, index
+//     word 7      key and mask
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Address([u32; 8]);
+
+impl HashFamily {
+    pub const ALL: [HashFamily; 2] = [HashFamily::Sha256, HashFamily::Shake256];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashFamily::Sha256 => "SHA2-256",
+            HashFamily::Shake256 => "SHAKE256",
+        }
+    }
+
+    // Byte identifying the family in serialized keys and signatures
+    pub fn id(&self) -> u8 {
+        match self {
+            HashFamily::Sha256 => 1,
+            HashFamily::Shake256 => 2,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Result<HashFamily, Error> {
+        HashFamily::ALL
+            .into_iter()
+            .find(|family| family.id() == id)
+            .ok_or_else(|| Error::UnknownAlgorithm(format!("hash family {}", id)))
+    }
+
+    // Plain hash of a message, what a one-time signature signs
+    pub fn hash(&self, message: &[u8]) -> HashValue {
+        self.hash_parts(&[message])
+    }
+
+    // Chaining function of WOTS+
+    pub fn f(&self, key: &HashValue, message: &HashValue) -> HashValue {
+        self.keyed(PADDING_F, key, &[message])
+    }
+
+    // Compression of two nodes of a tree
+    pub fn h(&self, key: &HashValue, left: &HashValue, right: &HashValue) -> HashValue {
+        self.keyed(PADDING_H, key, &[left, right])
+    }
+
+    // Randomized message hash, `key` is the randomness, the root and the index
+    pub fn h_msg(&self, key: &[u8], message: &[u8]) -> HashValue {
+        self.hash_parts(&[&padding(PADDING_H_MSG), key, message])
+    }
+
+    // Keys and bitmasks of the hash calls, from the public seed and the address
+    pub fn prf(&self, key: &HashValue, address: &Address) -> HashValue {
+        self.keyed(PADDING_PRF, key, &[&address.to_bytes()])
+    }
+
+    // Secret values from the secret seed (SP 800-208 section 5): the public seed is included
+    // so two keys sharing a secret seed by mistake still have unrelated secrets
+    pub fn prf_keygen(&self, secret_seed: &HashValue, public_seed: &HashValue, address: &Address) -> HashValue {
+        self.keyed(PADDING_PRF_KEYGEN, secret_seed, &[public_seed, &address.to_bytes()])
+    }
+
+    fn keyed(&self, padding_value: u8, key: &[u8], message: &[&[u8]]) -> HashValue {
+        let padding = padding(padding_value);
+        let parts: Vec<&[u8]> = [&padding[..], key].into_iter().chain(message.iter().copied()).collect();
+        self.hash_parts(&parts)
+    }
+
+    fn hash_parts(&self, parts: &[&[u8]]) -> HashValue {
+        let mut output = [0u8; N];
+        match self {
+            HashFamily::Sha256 => {
+                let mut hasher = Hasher::new(Algorithm::Sha256);
+                parts.iter().for_each(|part| hasher.update(part));
+                output.copy_from_slice(hasher.finalize().as_bytes());
+            }
+            HashFamily::Shake256 => {
+                let mut xof = Xof::shake(XofAlgorithm::Shake256);
+                parts.iter().for_each(|part| xof.update(part));
+                xof.finalize_into(&mut output);
+            }
+        }
+        output
+    }
+}
+
+impl fmt::Display for HashFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for HashFamily {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+            "sha256" | "sha2256" => Ok(HashFamily::Sha256),
+            "shake256" => Ok(HashFamily::Shake256),
+            _ => Err(Error::UnknownAlgorithm(s.to_string())),
+        }
+    }
+}
+
+impl Address {
+    pub const TYPE_OTS: u32 = 0;
+    pub const TYPE_LTREE: u32 = 1;
+    pub const TYPE_HASH_TREE: u32 = 2;
+
+    pub fn set_layer(&mut self, layer: u32) {
+        self.0[0] = layer;
+    }
+
+    pub fn set_tree(&mut self, tree: u64) {
+        self.0[1] = (tree >> 32) as u32;
+        self.0[2] = tree as u32;
+    }
+
+    // Changing the type clears the words that depend on it
+    pub fn set_type(&mut self, kind: u32) {
+        self.0[3] = kind;
+        self.0[4..].fill(0);
+    }
+
+    // OTS address, L-tree address or padding, depending on the type
+    pub fn set_leaf(&mut self, leaf: u32) {
+        self.0[4] = leaf;
+    }
+
+    pub fn set_chain(&mut self, chain: u32) {
+        self.0[5] = chain;
+    }
+
+    pub fn set_hash(&mut self, hash: u32) {
+        self.0[6] = hash;
+    }
+
+    pub fn set_tree_height(&mut self, height: u32) {
+        self.0[5] = height;
+    }
+
+    pub fn set_tree_index(&mut self, index: u32) {
+        self.0[6] = index;
+    }
+
+    pub fn tree_index(&self) -> u32 {
+        self.0[6]
+    }
+
+    pub fn set_key_and_mask(&mut self, key_and_mask: u32) {
+        self.0[7] = key_and_mask;
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(self.0) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8; 32]) -> Address {
+        let mut words = [0u32; 8];
+        for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+            *word = u32::from_be_bytes(chunk.try_into().expect("4-byte chunks"));
+        }
+        Address(words)
+    }
+}
+
+// Splits serialized keys and signatures into hash values, the caller has checked the length
+pub(crate) fn hash_values(bytes: &[u8]) -> Vec<HashValue> {
+    bytes.chunks_exact(N).map(|chunk| chunk.try_into().expect("N-byte chunks")).collect()
+}
+
+// Length check of serialized keys and signatures
+pub(crate) fn check_length(bytes: &[u8], expected: usize) -> Result<(), Error> {
+    match bytes.len() == expected {
+        true => Ok(()),
+        false => Err(Error::InvalidDecodedLength { expected, actual: bytes.len() }),
+    }
+}
+
+fn padding(value: u8) -> [u8; N] {
+    let mut padding = [0u8; N];
+    padding[N - 1] = value;
+    padding
+}
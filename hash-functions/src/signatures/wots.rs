@@ -0,0 +1,272 @@
+use std::fmt;
+
+use crate::signatures::primitives::{check_length, hash_values, Address, HashFamily, HashValue, N};
+use crate::utils::error::Error;
+
+// Winternitz parameter of the XMSS parameter sets: digits of 4 bits, chains of 15 steps
+pub const W: u32 = 16;
+const LOG_W: usize = 4;
+// 64 digits for the 256-bit digest and 3 for the checksum
+pub const LEN_1: usize = 8 * N / LOG_W;
+pub const LEN_2: usize = 3;
+pub const LEN: usize = LEN_1 + LEN_2;
+
+// WOTS+ one-time signature (RFC 8391 section 3). Each of the 67 secrets is the start of a hash chain
+// and the ends of the chains are the public key. A digit `d` of the message is signed by the value
+// `d` steps down its chain, the verifier walks the remaining steps. The checksum digits grow when
+// the message digits shrink, so no one can walk forward from a signature to sign another message.
+//
+// 2 KiB signatures instead of the 8 KiB of Lamport, for about 1000 hashes per signature.
+// A key signs once: two signatures of different messages reveal values further down some chains
+pub struct WotsSigningKey {
+    family: HashFamily,
+    secret_seed: HashValue,
+    public_seed: HashValue,
+    address: Address,
+    used: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WotsPublicKey {
+    family: HashFamily,
+    public_seed: HashValue,
+    address: Address,
+    chains: Vec<HashValue>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WotsSignature {
+    chains: Vec<HashValue>,
+}
+
+impl WotsSigningKey {
+    // Key at `address`, of type OTS, in a structure holding many keys such as the leaves of a Merkle
+    // signature tree. Keys of the same seeds must have different addresses
+    pub fn new(family: HashFamily, secret_seed: HashValue, public_seed: HashValue, address: Address) -> WotsSigningKey {
+        WotsSigningKey {
+            family,
+            secret_seed,
+            public_seed,
+            address,
+            used: false,
+        }
+    }
+
+    // Standalone key, both seeds are derived from `seed`
+    pub fn from_seed(family: HashFamily, seed: HashValue) -> WotsSigningKey {
+        let secret_seed = family.hash(&[&b"WOTS+ secret seed"[..], &seed].concat());
+        let public_seed = family.hash(&[&b"WOTS+ public seed"[..], &seed].concat());
+        WotsSigningKey::new(family, secret_seed, public_seed, Address::default())
+    }
+
+    pub fn generate(family: HashFamily) -> Result<WotsSigningKey, Error> {
+        let mut seed = [0u8; N];
+        getrandom::getrandom(&mut seed)?;
+        Ok(WotsSigningKey::from_seed(family, seed))
+    }
+
+    pub fn family(&self) -> HashFamily {
+        self.family
+    }
+
+    pub fn is_used(&self) -> bool {
+        self.used
+    }
+
+    pub fn public_key(&self) -> WotsPublicKey {
+        let chains = (0..LEN)
+            .map(|chain| self.chain_value(chain, W - 1))
+            .collect();
+        WotsPublicKey {
+            family: self.family,
+            public_seed: self.public_seed,
+            address: self.address,
+            chains,
+        }
+    }
+
+    // Signs the hash of the message
+    pub fn sign(&mut self, message: &[u8]) -> Result<WotsSignature, Error> {
+        let digest = self.family.hash(message);
+        self.sign_digest(&digest)
+    }
+
+    // Marks the key as used before returning the signature. Save the key with `to_bytes`
+    // after signing, a copy saved before would sign again
+    pub fn sign_digest(&mut self, digest: &HashValue) -> Result<WotsSignature, Error> {
+        if self.used {
+            return Err(Error::OneTimeKeyReused);
+        }
+        self.used = true;
+
+        let chains = digits(digest)
+            .iter()
+            .enumerate()
+            .map(|(chain, digit)| self.chain_value(chain, *digit))
+            .collect();
+        Ok(WotsSignature { chains })
+    }
+
+    // family || used || secret seed || public seed || address
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            &[self.family.id(), u8::from(self.used)][..],
+            &self.secret_seed,
+            &self.public_seed,
+            &self.address.to_bytes(),
+        ]
+        .concat()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<WotsSigningKey, Error> {
+        check_length(bytes, 2 + 3 * N)?;
+        Ok(WotsSigningKey {
+            family: HashFamily::from_id(bytes[0])?,
+            used: bytes[1] != 0,
+            secret_seed: bytes[2..2 + N].try_into().expect("length checked"),
+            public_seed: bytes[2 + N..2 + 2 * N].try_into().expect("length checked"),
+            address: Address::from_bytes(bytes[2 + 2 * N..].try_into().expect("length checked")),
+        })
+    }
+
+    // Value `steps` steps down the chain from its secret
+    fn chain_value(&self, chain: usize, steps: u32) -> HashValue {
+        let mut address = self.address;
+        address.set_chain(chain as u32);
+        address.set_hash(0);
+        let secret = self.family.prf_keygen(&self.secret_seed, &self.public_seed, &address);
+        walk_chain(self.family, &self.public_seed, &mut address, secret, 0, steps)
+    }
+}
+
+impl WotsPublicKey {
+    pub fn family(&self) -> HashFamily {
+        self.family
+    }
+
+    pub fn public_seed(&self) -> &HashValue {
+        &self.public_seed
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    // Ends of the chains, the values a Merkle tree compresses into a leaf
+    pub fn chains(&self) -> &[HashValue] {
+        &self.chains
+    }
+
+    pub fn verify(&self, message: &[u8], signature: &WotsSignature) -> Result<(), Error> {
+        self.verify_digest(&self.family.hash(message), signature)
+    }
+
+    pub fn verify_digest(&self, digest: &HashValue, signature: &WotsSignature) -> Result<(), Error> {
+        let chains = public_key_from_signature(self.family, &self.public_seed, self.address, digest, signature);
+        match chains == self.chains {
+            true => Ok(()),
+            false => Err(Error::InvalidSignature),
+        }
+    }
+
+    // family || public seed || address || ends of the 67 chains
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = [&[self.family.id()][..], &self.public_seed, &self.address.to_bytes()].concat();
+        self.chains.iter().for_each(|chain| bytes.extend_from_slice(chain));
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<WotsPublicKey, Error> {
+        check_length(bytes, 1 + 2 * N + LEN * N)?;
+        Ok(WotsPublicKey {
+            family: HashFamily::from_id(bytes[0])?,
+            public_seed: bytes[1..1 + N].try_into().expect("length checked"),
+            address: Address::from_bytes(bytes[1 + N..1 + 2 * N].try_into().expect("length checked")),
+            chains: hash_values(&bytes[1 + 2 * N..]),
+        })
+    }
+}
+
+impl WotsSignature {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.chains.concat()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<WotsSignature, Error> {
+        check_length(bytes, LEN * N)?;
+        Ok(WotsSignature { chains: hash_values(bytes) })
+    }
+}
+
+// The seeds are secret, they are not printed
+impl fmt::Debug for WotsSigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WotsSigningKey")
+            .field("family", &self.family)
+            .field("address", &self.address)
+            .field("used", &self.used)
+            .finish_non_exhaustive()
+    }
+}
+
+// WOTS_pkFromSig: walks every chain from the signed digit to its end. The result equals the
+// public key only for a valid signature, a Merkle tree checks it against its root instead
+pub fn public_key_from_signature(
+    family: HashFamily,
+    public_seed: &HashValue,
+    address: Address,
+    digest: &HashValue,
+    signature: &WotsSignature,
+) -> Vec<HashValue> {
+    let mut address = address;
+    digits(digest)
+        .iter()
+        .zip(&signature.chains)
+        .enumerate()
+        .map(|(chain, (digit, value))| {
+            address.set_chain(chain as u32);
+            walk_chain(family, public_seed, &mut address, *value, *digit, W - 1 - digit)
+        })
+        .collect()
+}
+
+// Chaining function `c` of RFC 8391: `steps` applications of F with a fresh key and bitmask each
+fn walk_chain(
+    family: HashFamily,
+    public_seed: &HashValue,
+    address: &mut Address,
+    value: HashValue,
+    start: u32,
+    steps: u32,
+) -> HashValue {
+    let mut value = value;
+    for position in start..start + steps {
+        address.set_hash(position);
+        address.set_key_and_mask(0);
+        let key = family.prf(public_seed, address);
+        address.set_key_and_mask(1);
+        let mask = family.prf(public_seed, address);
+        for (byte, mask_byte) in value.iter_mut().zip(mask) {
+            *byte ^= mask_byte;
+        }
+        value = family.f(&key, &value);
+    }
+    address.set_key_and_mask(0);
+    value
+}
+
+// Base-16 digits of the digest followed by the 3 digits of the checksum sum(15 - digit)
+fn digits(digest: &HashValue) -> [u32; LEN] {
+    let mut digits = [0u32; LEN];
+    for (i, byte) in digest.iter().enumerate() {
+        digits[2 * i] = u32::from(byte >> 4);
+        digits[2 * i + 1] = u32::from(byte & 0x0f);
+    }
+    // At most 64 * 15 = 960, it fits the 12 bits of the 3 digits
+    let checksum: u32 = digits[..LEN_1].iter().map(|digit| W - 1 - digit).sum();
+    for (i, digit) in digits[LEN_1..].iter_mut().enumerate() {
+        *digit = (checksum >> (4 * (LEN_2 - 1 - i))) & 0x0f;
+    }
+    digits
+}
+
@@ -0,0 +1,303 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::algorithms::hasher::{digest, Hasher};
+use crate::elements::{algorithm::Algorithm, digest::Digest, multihash::Multihash};
+use crate::utils::error::Error;
+
+const OBJECTS_DIR: &str = "objects";
+const REFS_DIR: &str = "refs";
+const TMP_DIR: &str = "tmp";
+
+// Hex characters of the fan-out directory: 256 directories, so a million blobs
+// leave about 4000 files per directory
+const FAN_OUT_LEN: usize = 2;
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+// Unique names of the temporary files of this process
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// Content-addressable storage in a local directory: a blob is stored once under the digest of its bytes.
+//
+//     objects/<algorithm>/<2 first hex digits>/<other hex digits>
+//     refs/<name>     multihash of a blob in hex, the roots kept by the garbage collection
+//     tmp/            blobs being written, renamed into objects/ once their digest is known
+//
+// The algorithm of the store addresses the new blobs, blobs of other algorithms can still be read.
+// Blobs are never modified in place, a reader sees the whole blob or nothing
+#[derive(Clone, Debug)]
+pub struct BlobStore {
+    root: PathBuf,
+    algorithm: Algorithm,
+}
+
+// What a garbage collection found
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GcReport {
+    pub kept: usize,
+    pub removed: usize,
+    pub freed_bytes: u64,
+}
+
+impl BlobStore {
+    // Creates the directories if needed, opening an existing store keeps its blobs
+    pub fn open<P: AsRef<Path>>(root: P, algorithm: Algorithm) -> Result<BlobStore, Error> {
+        let root = root.as_ref().to_path_buf();
+        for dir in [OBJECTS_DIR, REFS_DIR, TMP_DIR] {
+            fs::create_dir_all(root.join(dir))?;
+        }
+        Ok(BlobStore { root, algorithm })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    // Storing the same bytes twice keeps a single copy
+    pub fn put(&self, data: &[u8]) -> Result<Digest, Error> {
+        let address = digest(self.algorithm, data);
+        if self.contains(&address) {
+            return Ok(address);
+        }
+
+        let tmp_path = self.tmp_path();
+        let written = File::create(&tmp_path).and_then(|mut file| {
+            file.write_all(data)?;
+            file.sync_all()
+        });
+        self.commit(tmp_path, &address, written)?;
+        Ok(address)
+    }
+
+    // Streams the reader to a temporary file while hashing it, the content is never fully in memory
+    pub fn put_reader<R: Read>(&self, mut reader: R) -> Result<Digest, Error> {
+        let tmp_path = self.tmp_path();
+        let mut hasher = Hasher::new(self.algorithm);
+        let written = File::create(&tmp_path).and_then(|mut file| {
+            let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+            loop {
+                let read = match reader.read(&mut buffer) {
+                    Ok(0) => break,
+                    Ok(n) => n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                };
+                hasher.update(&buffer[..read]);
+                file.write_all(&buffer[..read])?;
+            }
+            file.sync_all()
+        });
+
+        let address = hasher.finalize();
+        self.commit(tmp_path, &address, written)?;
+        Ok(address)
+    }
+
+    pub fn put_file<P: AsRef<Path>>(&self, path: P) -> Result<Digest, Error> {
+        self.put_reader(File::open(path)?)
+    }
+
+    pub fn contains(&self, address: &Digest) -> bool {
+        self.blob_path(address).is_file()
+    }
+
+    // The bytes are hashed again before being returned: a blob modified or damaged on disk
+    // is reported as a `DigestMismatch` instead of silently returning wrong data
+    pub fn get(&self, address: &Digest) -> Result<Vec<u8>, Error> {
+        let data = match fs::read(self.blob_path(address)) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(Error::BlobNotFound(address.to_hex())),
+            Err(e) => return Err(Error::Io(e)),
+        };
+        match digest(address.algorithm(), &data) == *address {
+            true => Ok(data),
+            false => Err(Error::DigestMismatch),
+        }
+    }
+
+    pub fn remove(&self, address: &Digest) -> Result<(), Error> {
+        match fs::remove_file(self.blob_path(address)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Err(Error::BlobNotFound(address.to_hex())),
+            Err(e) => Err(Error::Io(e)),
+        }
+    }
+
+    // Addresses of all the stored blobs, whatever their algorithm, sorted
+    pub fn list(&self) -> Result<Vec<Digest>, Error> {
+        let mut addresses: Vec<Digest> = self.blobs()?.into_iter().map(|(address, _)| address).collect();
+        addresses.sort_by(|a, b| (a.algorithm().name(), a.as_bytes()).cmp(&(b.algorithm().name(), b.as_bytes())));
+        Ok(addresses)
+    }
+
+    // Names a blob so the garbage collection keeps it. The blob must be stored
+    pub fn set_ref(&self, name: &str, address: &Digest) -> Result<(), Error> {
+        let path = self.ref_path(name)?;
+        if !self.contains(address) {
+            return Err(Error::BlobNotFound(address.to_hex()));
+        }
+
+        let tmp_path = self.tmp_path();
+        fs::write(&tmp_path, Multihash::from(address).to_hex())?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn get_ref(&self, name: &str) -> Result<Option<Digest>, Error> {
+        match fs::read_to_string(self.ref_path(name)?) {
+            Ok(text) => Multihash::from_hex(text.trim()).and_then(|multihash| multihash.to_digest()).map(Some),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::Io(e)),
+        }
+    }
+
+    // True if the reference existed
+    pub fn remove_ref(&self, name: &str) -> Result<bool, Error> {
+        match fs::remove_file(self.ref_path(name)?) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(Error::Io(e)),
+        }
+    }
+
+    // References sorted by name
+    pub fn refs(&self) -> Result<Vec<(String, Digest)>, Error> {
+        let mut refs = Vec::new();
+        for entry in fs::read_dir(self.root.join(REFS_DIR))? {
+            let name = entry?.file_name();
+            let Some(name) = name.to_str().filter(|name| valid_ref_name(name)) else {
+                continue;
+            };
+            if let Some(address) = self.get_ref(name)? {
+                refs.push((name.to_string(), address));
+            }
+        }
+        refs.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(refs)
+    }
+
+    // Removes every blob no reference points to, the empty fan-out directories and the temporary
+    // files left by interrupted writes. A blob stored while the collection runs may be removed
+    // before it gets a reference, the store must not be written meanwhile
+    pub fn gc(&self) -> Result<GcReport, Error> {
+        let referenced: HashSet<Digest> = self.refs()?.into_iter().map(|(_, address)| address).collect();
+
+        let mut report = GcReport::default();
+        for (address, path) in self.blobs()? {
+            if referenced.contains(&address) {
+                report.kept += 1;
+                continue;
+            }
+            report.freed_bytes += fs::metadata(&path)?.len();
+            fs::remove_file(&path)?;
+            report.removed += 1;
+        }
+
+        for algorithm in Algorithm::ALL {
+            let algorithm_dir = self.root.join(OBJECTS_DIR).join(directory_name(algorithm));
+            if !algorithm_dir.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(&algorithm_dir)? {
+                // Fails on the directories still holding blobs
+                let _ = fs::remove_dir(entry?.path());
+            }
+        }
+        for entry in fs::read_dir(self.root.join(TMP_DIR))? {
+            fs::remove_file(entry?.path())?;
+        }
+        Ok(report)
+    }
+
+    // Moves a complete temporary file to its address, or deletes it when the write failed
+    // or the blob is already stored
+    fn commit(&self, tmp_path: PathBuf, address: &Digest, written: io::Result<()>) -> Result<(), Error> {
+        if let Err(e) = written {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(Error::Io(e));
+        }
+
+        let path = self.blob_path(address);
+        if path.is_file() {
+            fs::remove_file(&tmp_path)?;
+            return Ok(());
+        }
+        fs::create_dir_all(path.parent().expect("blob paths have a fan-out directory"))?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    fn blob_path(&self, address: &Digest) -> PathBuf {
+        let hex = address.to_hex();
+        let (fan_out, name) = hex.split_at(FAN_OUT_LEN);
+        self.root
+            .join(OBJECTS_DIR)
+            .join(directory_name(address.algorithm()))
+            .join(fan_out)
+            .join(name)
+    }
+
+    fn ref_path(&self, name: &str) -> Result<PathBuf, Error> {
+        match valid_ref_name(name) {
+            true => Ok(self.root.join(REFS_DIR).join(name)),
+            false => Err(Error::InvalidPath(format!("invalid reference name {:?}", name))),
+        }
+    }
+
+    fn tmp_path(&self) -> PathBuf {
+        let counter = TMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+        self.root.join(TMP_DIR).join(format!("{}-{}", process::id(), counter))
+    }
+
+    // Every blob with its path. Files whose name is not a digest of their directory are ignored
+    fn blobs(&self) -> Result<Vec<(Digest, PathBuf)>, Error> {
+        let mut blobs = Vec::new();
+        for algorithm in Algorithm::ALL {
+            let algorithm_dir = self.root.join(OBJECTS_DIR).join(directory_name(algorithm));
+            if !algorithm_dir.is_dir() {
+                continue;
+            }
+            for fan_out in fs::read_dir(&algorithm_dir)? {
+                let fan_out = fan_out?;
+                let Some(prefix) = fan_out.file_name().to_str().map(str::to_string) else {
+                    continue;
+                };
+                if !fan_out.file_type()?.is_dir() {
+                    continue;
+                }
+                for entry in fs::read_dir(fan_out.path())? {
+                    let entry = entry?;
+                    let hex = match entry.file_name().to_str() {
+                        Some(name) => format!("{}{}", prefix, name),
+                        None => continue,
+                    };
+                    if let Ok(address) = Digest::from_hex(algorithm, &hex) {
+                        blobs.push((address, entry.path()));
+                    }
+                }
+            }
+        }
+        Ok(blobs)
+    }
+}
+
+// `sha-256`, `sha3-256`, `sha-512-256`: the name without the characters file systems reject
+fn directory_name(algorithm: Algorithm) -> String {
+    algorithm.name().to_ascii_lowercase().replace('/', "-")
+}
+
+// A single path component, so a reference cannot point outside of refs/
+fn valid_ref_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || b"._-".contains(&b))
+}
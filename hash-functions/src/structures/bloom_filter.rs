@@ -0,0 +1,190 @@
+use std::f64::consts::LN_2;
+
+use crate::algorithms::hasher::Hasher;
+use crate::algorithms::xof_algo::Xof;
+use crate::elements::multihash::HashFunction;
+use crate::utils::error::Error;
+
+// Random key of a filter, sent along with it. Without the key nobody can craft items
+// that all land on the same bits
+pub const KEY_LEN: usize = 16;
+
+// More hash functions than this cost time for a negligible gain
+const MAX_HASHES: u32 = 32;
+
+// Bytes before the bit array in the serialized form: function code, key, bits, hashes and items
+const HEADER_LEN: usize = 8 + KEY_LEN + 8 + 4 + 8;
+
+// Set membership with false positives and no false negatives: an item that was inserted is always
+// found, an item that was not is found with probability `false_positive_rate()`.
+// Items cannot be removed, see `CuckooFilter` for that.
+//
+// The `k` bit positions come from a single keyed hash of the item with the double hashing of
+// Kirsch and Mitzenmacher: g_i = h1 + i * h2 mod m. The hash is any function of the multihash table,
+// SHA-256 and SHAKE128 being the usual choices
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BloomFilter {
+    function: HashFunction,
+    key: [u8; KEY_LEN],
+    num_bits: u64,
+    num_hashes: u32,
+    len: u64,
+    bits: Vec<u8>,
+}
+
+impl BloomFilter {
+    // Smallest filter holding `expected_items` with the target false positive rate:
+    // m = -n ln(p) / ln(2)^2 bits and k = m / n ln(2) hashes
+    pub fn new(function: HashFunction, expected_items: u64, false_positive_rate: f64) -> Result<BloomFilter, Error> {
+        if expected_items == 0 || !(false_positive_rate > 0.0 && false_positive_rate < 1.0) {
+            return Err(Error::InvalidParameter(
+                "a Bloom filter needs at least one item and a false positive rate between 0 and 1".to_string(),
+            ));
+        }
+        let items = expected_items as f64;
+        let num_bits = (-items * false_positive_rate.ln() / (LN_2 * LN_2)).ceil().max(8.0) as u64;
+        let num_hashes = ((num_bits as f64 / items) * LN_2).round().clamp(1.0, MAX_HASHES as f64) as u32;
+
+        let mut key = [0u8; KEY_LEN];
+        getrandom::getrandom(&mut key)?;
+        BloomFilter::with_parameters(function, key, num_bits, num_hashes)
+    }
+
+    // Filter with explicit sizes and key, two filters built the same way have the same bits
+    pub fn with_parameters(
+        function: HashFunction,
+        key: [u8; KEY_LEN],
+        num_bits: u64,
+        num_hashes: u32,
+    ) -> Result<BloomFilter, Error> {
+        if num_bits == 0 || !(1..=MAX_HASHES).contains(&num_hashes) {
+            return Err(Error::InvalidParameter(format!(
+                "a Bloom filter needs bits and between 1 and {} hashes",
+                MAX_HASHES
+            )));
+        }
+        Ok(BloomFilter {
+            function,
+            key,
+            num_bits,
+            num_hashes,
+            len: 0,
+            bits: vec![0; num_bits.div_ceil(8) as usize],
+        })
+    }
+
+    pub fn function(&self) -> HashFunction {
+        self.function
+    }
+
+    pub fn num_bits(&self) -> u64 {
+        self.num_bits
+    }
+
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    // Number of insertions, an item inserted twice counts twice
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, item: &[u8]) {
+        for position in self.positions(item) {
+            self.bits[(position / 8) as usize] |= 1 << (position % 8);
+        }
+        self.len = self.len.saturating_add(1);
+    }
+
+    pub fn contains(&self, item: &[u8]) -> bool {
+        self.positions(item)
+            .all(|position| self.bits[(position / 8) as usize] & (1 << (position % 8)) != 0)
+    }
+
+    // (1 - e^(-kn/m))^k for the items inserted so far
+    pub fn false_positive_rate(&self) -> f64 {
+        let k = self.num_hashes as f64;
+        (1.0 - (-k * self.len as f64 / self.num_bits as f64).exp()).powf(k)
+    }
+
+    // function code (u64 BE) || key || bits (u64 BE) || hashes (u32 BE) || items (u64 BE) || bit array
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.bits.len());
+        bytes.extend_from_slice(&self.function.code().to_be_bytes());
+        bytes.extend_from_slice(&self.key);
+        bytes.extend_from_slice(&self.num_bits.to_be_bytes());
+        bytes.extend_from_slice(&self.num_hashes.to_be_bytes());
+        bytes.extend_from_slice(&self.len.to_be_bytes());
+        bytes.extend_from_slice(&self.bits);
+        bytes
+    }
+
+    // The sizes come from the sender, the bit array is checked against them before anything is allocated
+    pub fn from_bytes(bytes: &[u8]) -> Result<BloomFilter, Error> {
+        if bytes.len() < HEADER_LEN {
+            return Err(Error::InvalidDecodedLength { expected: HEADER_LEN, actual: bytes.len() });
+        }
+        let (header, bits) = bytes.split_at(HEADER_LEN);
+        let num_bits = u64::from_be_bytes(header[8 + KEY_LEN..16 + KEY_LEN].try_into().expect("8 bytes"));
+        if num_bits.div_ceil(8) != bits.len() as u64 {
+            return Err(Error::InvalidDecodedLength {
+                expected: HEADER_LEN.saturating_add(usize::try_from(num_bits.div_ceil(8)).unwrap_or(usize::MAX)),
+                actual: bytes.len(),
+            });
+        }
+        let mut filter = BloomFilter::with_parameters(
+            HashFunction::from_code(u64::from_be_bytes(header[..8].try_into().expect("8 bytes")))?,
+            header[8..8 + KEY_LEN].try_into().expect("key length"),
+            num_bits,
+            u32::from_be_bytes(header[16 + KEY_LEN..20 + KEY_LEN].try_into().expect("4 bytes")),
+        )?;
+        filter.bits.copy_from_slice(bits);
+        filter.len = u64::from_be_bytes(header[20 + KEY_LEN..].try_into().expect("8 bytes"));
+
+        // Each insertion sets between 1 and k bits, and nothing beyond the last bit
+        let set_bits: u64 = filter.bits.iter().map(|byte| u64::from(byte.count_ones())).sum();
+        let used_in_last = ((num_bits - 1) % 8 + 1) as u32;
+        let padding = filter.bits.last().map_or(0, |last| last.checked_shr(used_in_last).unwrap_or(0));
+        if (filter.len == 0) != (set_bits == 0)
+            || set_bits > filter.len.saturating_mul(u64::from(filter.num_hashes))
+            || padding != 0
+        {
+            return Err(Error::InvalidParameter("the Bloom filter bits do not match its items".to_string()));
+        }
+        Ok(filter)
+    }
+
+    fn positions(&self, item: &[u8]) -> impl Iterator<Item = u64> {
+        let (h1, h2) = item_hash(self.function, &self.key, item);
+        let num_bits = self.num_bits;
+        (0..u64::from(self.num_hashes)).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+}
+
+// Two 64-bit values from the first 16 bytes of H(key || item). The second one is odd
+// so the positions g_i do not repeat when the number of bits is a power of two
+pub(crate) fn item_hash(function: HashFunction, key: &[u8], item: &[u8]) -> (u64, u64) {
+    let mut output = [0u8; 16];
+    match function {
+        HashFunction::Fixed(algorithm) => {
+            let mut hasher = Hasher::new(algorithm);
+            hasher.update(key);
+            hasher.update(item);
+            output.copy_from_slice(&hasher.finalize().as_bytes()[..16]);
+        }
+        HashFunction::Xof(algorithm) => {
+            let mut xof = Xof::shake(algorithm);
+            xof.update(key);
+            xof.update(item);
+            xof.finalize_into(&mut output);
+        }
+    }
+    let h1 = u64::from_le_bytes(output[..8].try_into().expect("8 bytes"));
+    let h2 = u64::from_le_bytes(output[8..].try_into().expect("8 bytes"));
+    (h1, h2 | 1)
+}
@@ -0,0 +1,215 @@
+use std::io::{self, Read};
+
+use crate::algorithms::hasher::digest;
+use crate::elements::{algorithm::Algorithm, digest::Digest};
+use crate::utils::error::Error;
+
+// Below this the chunk metadata costs more than the deduplication saves
+pub const MIN_CHUNK_SIZE: usize = 64;
+// Chunks are held in memory one at a time
+pub const MAX_CHUNK_SIZE: usize = 1 << 26;
+
+// Random values of the gear hash, one per byte value. Generated by splitmix64 from 0 at compile time:
+// the chunk boundaries of a file never change between versions of the crate
+const GEAR: [u64; 256] = gear_table();
+
+// Chunk sizes of FastCDC (Xia et al., USENIX ATC 2016). The average is only the target
+// of the distribution, the actual chunks are between `min_size` and `max_size`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ChunkerConfig {
+    min_size: usize,
+    avg_size: usize,
+    max_size: usize,
+    // Harder condition before the average size, easier after: the sizes concentrate around the average
+    mask_small: u64,
+    mask_large: u64,
+}
+
+// A piece of the input with its position and digest, the unit of deduplication
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Chunk {
+    pub offset: u64,
+    pub data: Vec<u8>,
+    pub digest: Digest,
+}
+
+// Content-defined chunking of a stream: the boundaries depend on the bytes around them only,
+// so inserting or removing data in the middle of a file changes the chunks around the edit
+// and leaves the others, and their digests, as they were
+pub struct Chunker<R: Read> {
+    reader: R,
+    algorithm: Algorithm,
+    config: ChunkerConfig,
+    buffer: Vec<u8>,
+    offset: u64,
+    eof: bool,
+}
+
+impl ChunkerConfig {
+    // The average is rounded to the nearest power of two, the number of bits the gear hash must match
+    pub fn new(min_size: usize, avg_size: usize, max_size: usize) -> Result<ChunkerConfig, Error> {
+        if min_size < MIN_CHUNK_SIZE || max_size > MAX_CHUNK_SIZE || !(min_size <= avg_size && avg_size <= max_size) {
+            return Err(Error::InvalidParameter(format!(
+                "chunk sizes must satisfy {} <= min <= avg <= max <= {}, got {}, {} and {}",
+                MIN_CHUNK_SIZE, MAX_CHUNK_SIZE, min_size, avg_size, max_size
+            )));
+        }
+        // Normalization level 1 of the paper: one bit more before the average, one less after
+        let bits = (avg_size as f64).log2().round() as u32;
+        Ok(ChunkerConfig {
+            min_size,
+            avg_size,
+            max_size,
+            mask_small: high_bits_mask(bits + 1),
+            mask_large: high_bits_mask(bits - 1),
+        })
+    }
+
+    pub fn min_size(&self) -> usize {
+        self.min_size
+    }
+
+    pub fn avg_size(&self) -> usize {
+        self.avg_size
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    // Length of the first chunk of `data`, `data` being the rest of the input
+    pub fn cut_point(&self, data: &[u8]) -> usize {
+        if data.len() <= self.min_size {
+            return data.len();
+        }
+        let end = data.len().min(self.max_size);
+        let normal = end.min(self.avg_size);
+
+        // The first `min_size` bytes cannot hold a boundary, they are not even hashed
+        let mut hash = 0u64;
+        for (i, byte) in data.iter().enumerate().take(normal).skip(self.min_size) {
+            hash = (hash << 1).wrapping_add(GEAR[*byte as usize]);
+            if hash & self.mask_small == 0 {
+                return i + 1;
+            }
+        }
+        for (i, byte) in data.iter().enumerate().take(end).skip(normal) {
+            hash = (hash << 1).wrapping_add(GEAR[*byte as usize]);
+            if hash & self.mask_large == 0 {
+                return i + 1;
+            }
+        }
+        end
+    }
+
+    // Boundaries of a whole buffer: the end offset of every chunk
+    pub fn boundaries(&self, data: &[u8]) -> Vec<usize> {
+        let mut boundaries = Vec::new();
+        let mut start = 0;
+        while start < data.len() {
+            start += self.cut_point(&data[start..]);
+            boundaries.push(start);
+        }
+        boundaries
+    }
+}
+
+// 2 KiB, 8 KiB and 64 KiB: the sizes of the paper and of most backup tools
+impl Default for ChunkerConfig {
+    fn default() -> Self {
+        ChunkerConfig::new(2 * 1024, 8 * 1024, 64 * 1024).expect("valid default sizes")
+    }
+}
+
+impl<R: Read> Chunker<R> {
+    pub fn new(reader: R, algorithm: Algorithm, config: ChunkerConfig) -> Chunker<R> {
+        Chunker {
+            reader,
+            algorithm,
+            config,
+            buffer: Vec::with_capacity(config.max_size),
+            offset: 0,
+            eof: false,
+        }
+    }
+
+    // A chunk is complete once the buffer holds `max_size` bytes or the whole rest of the input
+    fn fill_buffer(&mut self) -> io::Result<()> {
+        while !self.eof && self.buffer.len() < self.config.max_size {
+            let filled = self.buffer.len();
+            self.buffer.resize(self.config.max_size, 0);
+            match self.reader.read(&mut self.buffer[filled..]) {
+                Ok(n) => {
+                    self.buffer.truncate(filled + n);
+                    self.eof = n == 0;
+                }
+                Err(e) => {
+                    self.buffer.truncate(filled);
+                    if e.kind() != io::ErrorKind::Interrupted {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for Chunker<R> {
+    type Item = Result<Chunk, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.fill_buffer() {
+            return Some(Err(Error::Io(e)));
+        }
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        let len = self.config.cut_point(&self.buffer);
+        let data: Vec<u8> = self.buffer.drain(..len).collect();
+        let chunk = Chunk {
+            offset: self.offset,
+            digest: digest(self.algorithm, &data),
+            data,
+        };
+        self.offset += len as u64;
+        Some(Ok(chunk))
+    }
+}
+
+// Chunks of a buffer already in memory
+pub fn chunk_bytes(algorithm: Algorithm, config: ChunkerConfig, data: &[u8]) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    for end in config.boundaries(data) {
+        chunks.push(Chunk {
+            offset: start as u64,
+            data: data[start..end].to_vec(),
+            digest: digest(algorithm, &data[start..end]),
+        });
+        start = end;
+    }
+    chunks
+}
+
+// The gear hash shifts left, the high bits depend on the last 64 bytes and the low bits
+// on the last few only: the masks test the high bits
+fn high_bits_mask(bits: u32) -> u64 {
+    u64::MAX << (64 - bits)
+}
+
+const fn gear_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut state = 0u64;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
@@ -0,0 +1,244 @@
+use crate::elements::multihash::HashFunction;
+use crate::structures::bloom_filter::{item_hash, KEY_LEN};
+use crate::utils::error::Error;
+
+// Fingerprints per bucket, 4 allows a load of 95% (Fan et al., "Cuckoo Filter: Practically Better Than Bloom")
+pub const BUCKET_SIZE: usize = 4;
+
+// Relocations before an insertion gives up
+const MAX_KICKS: u32 = 500;
+
+const MAX_LOAD: f64 = 0.95;
+
+// function code, key, buckets, items, victim flag, victim bucket and victim fingerprint
+const HEADER_LEN: usize = 8 + KEY_LEN + 8 + 8 + 1 + 8 + 2;
+
+// Set membership like `BloomFilter`, with deletion. Each item is a 16-bit fingerprint stored in
+// one of two buckets, the second bucket is computed from the first and the fingerprint alone so
+// fingerprints can move between their buckets without the items (partial-key cuckoo hashing).
+// False positive rate about 2 * 4 / 2^16 = 1.2e-4, independent of the capacity.
+//
+// Only remove items that were inserted: removing another item with the same fingerprint and bucket
+// would make an inserted item disappear
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CuckooFilter {
+    function: HashFunction,
+    key: [u8; KEY_LEN],
+    buckets: Vec<[u16; BUCKET_SIZE]>,
+    len: u64,
+    // Fingerprint left without a bucket by the last failed relocation, the filter is full while it is set
+    victim: Option<(usize, u16)>,
+}
+
+impl CuckooFilter {
+    // Filter holding at least `capacity` items, the number of buckets is rounded up to a power of two
+    pub fn new(function: HashFunction, capacity: u64) -> Result<CuckooFilter, Error> {
+        if capacity == 0 {
+            return Err(Error::InvalidParameter("a cuckoo filter needs room for one item".to_string()));
+        }
+        let num_buckets = (capacity as f64 / (BUCKET_SIZE as f64 * MAX_LOAD)).ceil() as u64;
+        let mut key = [0u8; KEY_LEN];
+        getrandom::getrandom(&mut key)?;
+        CuckooFilter::with_parameters(function, key, num_buckets.next_power_of_two())
+    }
+
+    pub fn with_parameters(function: HashFunction, key: [u8; KEY_LEN], num_buckets: u64) -> Result<CuckooFilter, Error> {
+        if !num_buckets.is_power_of_two() || num_buckets > u32::MAX as u64 {
+            return Err(Error::InvalidParameter(format!(
+                "the number of buckets must be a power of two up to 2^31, not {}",
+                num_buckets
+            )));
+        }
+        Ok(CuckooFilter {
+            function,
+            key,
+            buckets: vec![[0; BUCKET_SIZE]; num_buckets as usize],
+            len: 0,
+            victim: None,
+        })
+    }
+
+    pub fn function(&self) -> HashFunction {
+        self.function
+    }
+
+    pub fn num_buckets(&self) -> usize {
+        self.buckets.len()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * BUCKET_SIZE
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn load_factor(&self) -> f64 {
+        self.len as f64 / self.capacity() as f64
+    }
+
+    // Stores the item, an item inserted twice takes two entries and must be removed twice.
+    // The insertion that fills the filter still succeeds, the following ones fail with `FilterFull`
+    pub fn insert(&mut self, item: &[u8]) -> Result<(), Error> {
+        if self.victim.is_some() {
+            return Err(Error::FilterFull);
+        }
+        let (bucket, fingerprint) = self.locate(item);
+        let alternate = self.alternate(bucket, fingerprint);
+        if self.put(bucket, fingerprint) || self.put(alternate, fingerprint) {
+            self.len += 1;
+            return Ok(());
+        }
+
+        // Evicts a fingerprint of the bucket and moves it to its other bucket, until one has room
+        let mut bucket = alternate;
+        let mut fingerprint = fingerprint;
+        for kick in 0..MAX_KICKS {
+            let slot = (mix(bucket as u64 ^ (u64::from(fingerprint) << 32) ^ (u64::from(kick) << 48)) % BUCKET_SIZE as u64) as usize;
+            std::mem::swap(&mut fingerprint, &mut self.buckets[bucket][slot]);
+            bucket = self.alternate(bucket, fingerprint);
+            if self.put(bucket, fingerprint) {
+                self.len += 1;
+                return Ok(());
+            }
+        }
+        self.victim = Some((bucket, fingerprint));
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn contains(&self, item: &[u8]) -> bool {
+        let (bucket, fingerprint) = self.locate(item);
+        let alternate = self.alternate(bucket, fingerprint);
+        self.buckets[bucket].contains(&fingerprint)
+            || self.buckets[alternate].contains(&fingerprint)
+            || self.victim.is_some_and(|victim| victim == (bucket, fingerprint) || victim == (alternate, fingerprint))
+    }
+
+    // Removes one entry of the item, false if it was not found
+    pub fn remove(&mut self, item: &[u8]) -> bool {
+        let (bucket, fingerprint) = self.locate(item);
+        let alternate = self.alternate(bucket, fingerprint);
+        if self.victim.is_some_and(|victim| victim == (bucket, fingerprint) || victim == (alternate, fingerprint)) {
+            self.victim = None;
+            self.len -= 1;
+            return true;
+        }
+        for index in [bucket, alternate] {
+            if let Some(slot) = self.buckets[index].iter().position(|entry| *entry == fingerprint) {
+                self.buckets[index][slot] = 0;
+                self.len -= 1;
+                // The freed entry may take the victim back
+                if let Some((victim_bucket, victim_fingerprint)) = self.victim.take() {
+                    let victim_alternate = self.alternate(victim_bucket, victim_fingerprint);
+                    if !self.put(victim_bucket, victim_fingerprint) && !self.put(victim_alternate, victim_fingerprint) {
+                        self.victim = Some((victim_bucket, victim_fingerprint));
+                    }
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    // function code (u64 BE) || key || buckets (u64 BE) || items (u64 BE) || victim flag
+    // || victim bucket (u64 BE) || victim fingerprint (u16 BE) || fingerprints (u16 BE)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (victim_bucket, victim_fingerprint) = self.victim.unwrap_or((0, 0));
+        let mut bytes = Vec::with_capacity(HEADER_LEN + 2 * self.capacity());
+        bytes.extend_from_slice(&self.function.code().to_be_bytes());
+        bytes.extend_from_slice(&self.key);
+        bytes.extend_from_slice(&(self.buckets.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(&self.len.to_be_bytes());
+        bytes.push(u8::from(self.victim.is_some()));
+        bytes.extend_from_slice(&(victim_bucket as u64).to_be_bytes());
+        bytes.extend_from_slice(&victim_fingerprint.to_be_bytes());
+        for fingerprint in self.buckets.iter().flatten() {
+            bytes.extend_from_slice(&fingerprint.to_be_bytes());
+        }
+        bytes
+    }
+
+    // The sizes come from the sender, the fingerprints are checked against them before anything is allocated
+    pub fn from_bytes(bytes: &[u8]) -> Result<CuckooFilter, Error> {
+        if bytes.len() < HEADER_LEN {
+            return Err(Error::InvalidDecodedLength { expected: HEADER_LEN, actual: bytes.len() });
+        }
+        let (header, fingerprints) = bytes.split_at(HEADER_LEN);
+        let read_u64 = |offset: usize| u64::from_be_bytes(header[offset..offset + 8].try_into().expect("8 bytes"));
+        let num_buckets = read_u64(8 + KEY_LEN);
+        let expected = num_buckets.checked_mul(2 * BUCKET_SIZE as u64);
+        if expected != Some(fingerprints.len() as u64) {
+            return Err(Error::InvalidDecodedLength {
+                expected: expected
+                    .and_then(|len| usize::try_from(len).ok())
+                    .map_or(usize::MAX, |len| HEADER_LEN.saturating_add(len)),
+                actual: bytes.len(),
+            });
+        }
+        let mut filter = CuckooFilter::with_parameters(
+            HashFunction::from_code(read_u64(0))?,
+            header[8..8 + KEY_LEN].try_into().expect("key length"),
+            num_buckets,
+        )?;
+        let victim_bucket = read_u64(25 + KEY_LEN);
+        let victim_fingerprint = u16::from_be_bytes(header[33 + KEY_LEN..].try_into().expect("2 bytes"));
+        filter.victim = match header[24 + KEY_LEN] {
+            0 => None,
+            1 if victim_bucket < filter.buckets.len() as u64 && victim_fingerprint != 0 => {
+                Some((victim_bucket as usize, victim_fingerprint))
+            }
+            _ => return Err(Error::InvalidParameter("invalid cuckoo filter victim".to_string())),
+        };
+        for (entry, fingerprint) in filter.buckets.iter_mut().flatten().zip(fingerprints.chunks_exact(2)) {
+            *entry = u16::from_be_bytes([fingerprint[0], fingerprint[1]]);
+        }
+
+        // Every item is one entry, `remove` relies on it
+        let occupied = filter.buckets.iter().flatten().filter(|entry| **entry != 0).count() + usize::from(filter.victim.is_some());
+        filter.len = read_u64(16 + KEY_LEN);
+        if filter.len != occupied as u64 {
+            return Err(Error::InvalidParameter(format!(
+                "the cuckoo filter holds {} entries, not {}",
+                occupied, filter.len
+            )));
+        }
+        Ok(filter)
+    }
+
+    // First bucket and fingerprint of the item. 0 marks an empty entry, it is never a fingerprint
+    fn locate(&self, item: &[u8]) -> (usize, u16) {
+        let (h1, h2) = item_hash(self.function, &self.key, item);
+        let bucket = (h1 & (self.buckets.len() as u64 - 1)) as usize;
+        let fingerprint = (h2 >> 48) as u16;
+        (bucket, fingerprint.max(1))
+    }
+
+    // The other bucket of a fingerprint, from either of them: i ^ hash(fingerprint)
+    fn alternate(&self, bucket: usize, fingerprint: u16) -> usize {
+        bucket ^ (mix(u64::from(fingerprint)) & (self.buckets.len() as u64 - 1)) as usize
+    }
+
+    fn put(&mut self, bucket: usize, fingerprint: u16) -> bool {
+        match self.buckets[bucket].iter().position(|entry| *entry == 0) {
+            Some(slot) => {
+                self.buckets[bucket][slot] = fingerprint;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// Finalizer of splitmix64, spreads the bits of a fingerprint over the bucket index
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
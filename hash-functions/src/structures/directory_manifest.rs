@@ -0,0 +1,150 @@
+use std::fmt;
+use std::fs::File;
+use std::path::{Component, Path};
+
+use rayon::prelude::*;
+use walkdir::WalkDir;
+
+use crate::algorithms::hasher::digest_reader;
+use crate::elements::{algorithm::Algorithm, digest::Digest};
+use crate::utils::checksum::{escape_name, needs_escape};
+use crate::utils::error::Error;
+use super::merkle_tree::MerkleTree;
+
+// A regular file of the tree, with its path relative to the root using `/` as separator
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+    pub digest: Digest,
+}
+
+// Digests of all the regular files below a directory, sorted by path so the same tree always gives
+// the same manifest, whatever the platform, the file system order or the number of threads.
+// Symbolic links are not followed and, as empty directories, they are not part of the manifest
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirectoryManifest {
+    algorithm: Algorithm,
+    entries: Vec<ManifestEntry>,
+}
+
+impl ManifestEntry {
+    // Unambiguous encoding of the entry used as Merkle leaf:
+    // path length (u64 BE) || path || size (u64 BE) || digest
+    pub fn leaf(&self) -> Vec<u8> {
+        let mut leaf = Vec::with_capacity(16 + self.path.len() + self.digest.as_bytes().len());
+        leaf.extend_from_slice(&(self.path.len() as u64).to_be_bytes());
+        leaf.extend_from_slice(self.path.as_bytes());
+        leaf.extend_from_slice(&self.size.to_be_bytes());
+        leaf.extend_from_slice(self.digest.as_bytes());
+        leaf
+    }
+}
+
+impl DirectoryManifest {
+    // Walks the directory and hashes the files in parallel on the rayon thread pool
+    // (RAYON_NUM_THREADS sets the number of threads)
+    pub fn build<P: AsRef<Path>>(algorithm: Algorithm, root: P) -> Result<DirectoryManifest, Error> {
+        let root = root.as_ref();
+        if !root.is_dir() {
+            return Err(Error::InvalidPath(format!("{} is not a directory", root.display())));
+        }
+
+        let mut files = Vec::new();
+        for entry in WalkDir::new(root).follow_links(false) {
+            let entry = entry?;
+            if entry.file_type().is_file() {
+                let relative = relative_path(root, entry.path())?;
+                files.push((relative, entry.into_path()));
+            }
+        }
+        // Byte order of the whole path, walkdir only sorts the names inside each directory
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let entries = files
+            .into_par_iter()
+            .map(|(path, full_path)| {
+                let file = File::open(&full_path)?;
+                let size = file.metadata()?.len();
+                let digest = digest_reader(algorithm, file)?;
+                Ok(ManifestEntry { path, size, digest })
+            })
+            .collect::<Result<Vec<ManifestEntry>, Error>>()?;
+
+        Ok(DirectoryManifest { algorithm, entries })
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn entries(&self) -> &[ManifestEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, path: &str) -> Option<&ManifestEntry> {
+        self.entries
+            .binary_search_by(|entry| entry.path.as_str().cmp(path))
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+
+    // RFC 6962 tree over the entries, so a single file can be proven against the root digest
+    pub fn merkle_tree(&self) -> MerkleTree {
+        let leaves: Vec<Vec<u8>> = self.entries.iter().map(ManifestEntry::leaf).collect();
+        MerkleTree::from_leaves(self.algorithm, &leaves)
+    }
+
+    // Single digest of the whole tree: any renamed, added, removed or modified file changes it
+    pub fn root_digest(&self) -> Digest {
+        self.merkle_tree().root()
+    }
+}
+
+// One line per file: `<digest>  <size>  <path>`. As in checksum lines, a path with a backslash
+// or a line break is escaped and the line starts with a backslash
+impl fmt::Display for ManifestEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match needs_escape(&self.path) {
+            true => write!(f, "\\{}  {}  {}", self.digest, self.size, escape_name(&self.path)),
+            false => write!(f, "{}  {}  {}", self.digest, self.size, self.path),
+        }
+    }
+}
+
+impl fmt::Display for DirectoryManifest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+// Path below the root with `/` separators. Paths that are not UTF-8 are rejected instead of
+// being lossily converted, two different files could end up with the same name
+fn relative_path(root: &Path, path: &Path) -> Result<String, Error> {
+    let relative = path
+        .strip_prefix(root)
+        .map_err(|_| Error::InvalidPath(path.display().to_string()))?;
+
+    let mut components = Vec::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(name) => match name.to_str() {
+                Some(name) => components.push(name),
+                None => return Err(Error::InvalidPath(path.display().to_string())),
+            },
+            _ => return Err(Error::InvalidPath(path.display().to_string())),
+        }
+    }
+    Ok(components.join("/"))
+}
@@ -0,0 +1,293 @@
+use crate::algorithms::hasher::{digest, Hasher};
+use crate::elements::{algorithm::Algorithm, digest::Digest};
+use crate::utils::error::Error;
+
+// RFC 6962 domain separation: leaves and inner nodes are hashed with a different prefix,
+// so an inner node can never be presented as a leaf (second preimage attack)
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+// Append-only Merkle tree as defined in RFC 6962 (Certificate Transparency).
+// Only the leaf hashes are stored, inner nodes are computed when a root or a proof is requested
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    algorithm: Algorithm,
+    leaves: Vec<Digest>,
+}
+
+// Proves that a leaf is at `leaf_index` in the tree of `tree_size` leaves.
+// The path goes from the sibling of the leaf up to the sibling of the root's child
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub leaf_index: usize,
+    pub tree_size: usize,
+    pub path: Vec<Digest>,
+}
+
+// Proves that the tree of `old_size` leaves is a prefix of the tree of `new_size` leaves,
+// that is, nothing was modified or removed, only appended
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsistencyProof {
+    pub old_size: usize,
+    pub new_size: usize,
+    pub path: Vec<Digest>,
+}
+
+impl MerkleTree {
+    pub fn new(algorithm: Algorithm) -> MerkleTree {
+        MerkleTree {
+            algorithm,
+            leaves: Vec::new(),
+        }
+    }
+
+    pub fn from_leaves<T: AsRef<[u8]>>(algorithm: Algorithm, leaves: &[T]) -> MerkleTree {
+        let mut tree = MerkleTree::new(algorithm);
+        for leaf in leaves {
+            tree.push(leaf.as_ref());
+        }
+        tree
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn push(&mut self, leaf: &[u8]) {
+        self.leaves.push(leaf_hash(self.algorithm, leaf));
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    pub fn root(&self) -> Digest {
+        self.subtree_root(0, self.leaves.len())
+    }
+
+    // Root of the tree when it only had the first `size` leaves
+    pub fn root_at(&self, size: usize) -> Result<Digest, Error> {
+        self.check_size(size)?;
+        Ok(self.subtree_root(0, size))
+    }
+
+    pub fn inclusion_proof(&self, leaf_index: usize) -> Result<InclusionProof, Error> {
+        self.inclusion_proof_at(leaf_index, self.leaves.len())
+    }
+
+    // Inclusion proof against the root of the tree of `tree_size` leaves
+    pub fn inclusion_proof_at(&self, leaf_index: usize, tree_size: usize) -> Result<InclusionProof, Error> {
+        self.check_size(tree_size)?;
+        if leaf_index >= tree_size {
+            return Err(Error::IndexOutOfBounds { index: leaf_index, size: tree_size });
+        }
+
+        let mut path = Vec::new();
+        self.inclusion_path(leaf_index, 0, tree_size, &mut path);
+        Ok(InclusionProof { leaf_index, tree_size, path })
+    }
+
+    pub fn consistency_proof(&self, old_size: usize) -> Result<ConsistencyProof, Error> {
+        self.consistency_proof_between(old_size, self.leaves.len())
+    }
+
+    pub fn consistency_proof_between(&self, old_size: usize, new_size: usize) -> Result<ConsistencyProof, Error> {
+        self.check_size(new_size)?;
+        if old_size > new_size {
+            return Err(Error::IndexOutOfBounds { index: old_size, size: new_size });
+        }
+
+        let mut path = Vec::new();
+        if old_size > 0 && old_size < new_size {
+            self.consistency_path(old_size, 0, new_size, true, &mut path);
+        }
+        Ok(ConsistencyProof { old_size, new_size, path })
+    }
+
+    fn check_size(&self, size: usize) -> Result<(), Error> {
+        match size <= self.leaves.len() {
+            true => Ok(()),
+            false => Err(Error::IndexOutOfBounds { index: size, size: self.leaves.len() }),
+        }
+    }
+
+    // MTH(D[start:end]) of RFC 6962 section 2.1
+    fn subtree_root(&self, start: usize, end: usize) -> Digest {
+        match end - start {
+            0 => digest(self.algorithm, b""),
+            1 => self.leaves[start].clone(),
+            size => {
+                let split = start + split_point(size);
+                node_hash(&self.subtree_root(start, split), &self.subtree_root(split, end))
+            }
+        }
+    }
+
+    // PATH(m, D[start:end]) of RFC 6962 section 2.1.1
+    fn inclusion_path(&self, index: usize, start: usize, end: usize, path: &mut Vec<Digest>) {
+        if end - start <= 1 {
+            return;
+        }
+        let split = start + split_point(end - start);
+        if index < split {
+            self.inclusion_path(index, start, split, path);
+            path.push(self.subtree_root(split, end));
+        } else {
+            self.inclusion_path(index, split, end, path);
+            path.push(self.subtree_root(start, split));
+        }
+    }
+
+    // SUBPROOF(m, D[start:end], b) of RFC 6962 section 2.1.2, `old_size` is relative to `start`
+    fn consistency_path(&self, old_size: usize, start: usize, end: usize, complete: bool, path: &mut Vec<Digest>) {
+        if old_size == end - start {
+            // The old tree is a complete subtree of the new one. Its root is only needed
+            // when the verifier cannot get it from the old root
+            if !complete {
+                path.push(self.subtree_root(start, end));
+            }
+            return;
+        }
+        let split = split_point(end - start);
+        if old_size <= split {
+            self.consistency_path(old_size, start, start + split, complete, path);
+            path.push(self.subtree_root(start + split, end));
+        } else {
+            self.consistency_path(old_size - split, start + split, end, false, path);
+            path.push(self.subtree_root(start, start + split));
+        }
+    }
+}
+
+impl InclusionProof {
+    // Rebuilds the root from the leaf and the path (RFC 9162 section 2.1.3.2)
+    pub fn verify(&self, leaf: &[u8], root: &Digest) -> Result<(), Error> {
+        if self.leaf_index >= self.tree_size || !same_algorithm(&self.path, root) {
+            return Err(Error::InvalidMerkleProof);
+        }
+
+        let mut index = self.leaf_index;
+        let mut last = self.tree_size - 1;
+        let mut computed = leaf_hash(root.algorithm(), leaf);
+        for sibling in &self.path {
+            if last == 0 {
+                return Err(Error::InvalidMerkleProof);
+            }
+            if index & 1 == 1 || index == last {
+                computed = node_hash(sibling, &computed);
+                // Skip the levels where the node is the last one and has no sibling
+                while index & 1 == 0 && index != 0 {
+                    index >>= 1;
+                    last >>= 1;
+                }
+            } else {
+                computed = node_hash(&computed, sibling);
+            }
+            index >>= 1;
+            last >>= 1;
+        }
+
+        match last == 0 && computed == *root {
+            true => Ok(()),
+            false => Err(Error::InvalidMerkleProof),
+        }
+    }
+}
+
+impl ConsistencyProof {
+    // Rebuilds both roots from the path (RFC 9162 section 2.1.4.2)
+    pub fn verify(&self, old_root: &Digest, new_root: &Digest) -> Result<(), Error> {
+        if self.old_size > self.new_size
+            || old_root.algorithm() != new_root.algorithm()
+            || !same_algorithm(&self.path, new_root)
+        {
+            return Err(Error::InvalidMerkleProof);
+        }
+        // An empty tree is a prefix of any tree
+        if self.old_size == 0 {
+            return Ok(());
+        }
+        if self.old_size == self.new_size {
+            return match self.path.is_empty() && old_root == new_root {
+                true => Ok(()),
+                false => Err(Error::InvalidMerkleProof),
+            };
+        }
+        if self.path.is_empty() {
+            return Err(Error::InvalidMerkleProof);
+        }
+
+        // When the old tree is a complete subtree its root is the start of the path
+        let mut path = self.path.iter();
+        let first = match self.old_size.is_power_of_two() {
+            true => old_root,
+            false => path.next().expect("path is not empty"),
+        };
+
+        let mut index = self.old_size - 1;
+        let mut last = self.new_size - 1;
+        while index & 1 == 1 {
+            index >>= 1;
+            last >>= 1;
+        }
+
+        let mut old_computed = first.clone();
+        let mut new_computed = first.clone();
+        for sibling in path {
+            if last == 0 {
+                return Err(Error::InvalidMerkleProof);
+            }
+            if index & 1 == 1 || index == last {
+                old_computed = node_hash(sibling, &old_computed);
+                new_computed = node_hash(sibling, &new_computed);
+                while index & 1 == 0 && index != 0 {
+                    index >>= 1;
+                    last >>= 1;
+                }
+            } else {
+                new_computed = node_hash(&new_computed, sibling);
+            }
+            index >>= 1;
+            last >>= 1;
+        }
+
+        match last == 0 && old_computed == *old_root && new_computed == *new_root {
+            true => Ok(()),
+            false => Err(Error::InvalidMerkleProof),
+        }
+    }
+}
+
+// H(0x00 || leaf)
+pub fn leaf_hash(algorithm: Algorithm, leaf: &[u8]) -> Digest {
+    let mut hasher = Hasher::new(algorithm);
+    hasher.update(&[LEAF_PREFIX]);
+    hasher.update(leaf);
+    hasher.finalize()
+}
+
+// H(0x01 || left || right), with the algorithm of the children
+pub fn node_hash(left: &Digest, right: &Digest) -> Digest {
+    let mut hasher = Hasher::new(left.algorithm());
+    hasher.update(&[NODE_PREFIX]);
+    hasher.update(left.as_bytes());
+    hasher.update(right.as_bytes());
+    hasher.finalize()
+}
+
+// Largest power of two smaller than `size`, the size of the left subtree
+fn split_point(size: usize) -> usize {
+    let mut split = 1;
+    while split << 1 < size {
+        split <<= 1;
+    }
+    split
+}
+
+fn same_algorithm(path: &[Digest], root: &Digest) -> bool {
+    path.iter().all(|node| node.algorithm() == root.algorithm())
+}
@@ -0,0 +1,6 @@
+pub mod merkle_tree;
+pub mod directory_manifest;
+pub mod blob_store;
+pub mod chunker;
+pub mod bloom_filter;
+pub mod cuckoo_filter;
@@ -0,0 +1,169 @@
+use std::path::Path;
+
+use crate::algorithms::hasher::digest_file;
+use crate::elements::algorithm::{Algorithm, XofAlgorithm};
+use crate::elements::digest::Digest;
+use super::error::Error;
+
+// Line layouts of the coreutils checksum tools
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChecksumFormat {
+    // `<hex>  <name>`, the default output of `sha256sum`
+    Gnu,
+    // `SHA256 (<name>) = <hex>`, the output of `sha256sum --tag`
+    Bsd,
+}
+
+// Builds one output line (without the line break) in the same way coreutils does.
+// The `binary` flag only changes the mode marker of the GNU format: `*` instead of a space
+pub fn format_line(digest: &Digest, name: &str, format: ChecksumFormat, binary: bool) -> String {
+    format_raw_line(digest.algorithm().tag(), &digest.to_hex(), name, format, binary)
+}
+
+// Same as `format_line` for the output of SHAKE128 or SHAKE256, whatever its length
+pub fn format_xof_line(
+    algorithm: XofAlgorithm,
+    output: &[u8],
+    name: &str,
+    format: ChecksumFormat,
+    binary: bool,
+) -> String {
+    format_raw_line(algorithm.name(), &hex::encode(output), name, format, binary)
+}
+
+fn format_raw_line(tag: &str, hex_digest: &str, name: &str, format: ChecksumFormat, binary: bool) -> String {
+    // Names with a backslash or a line break are escaped and the whole line is prefixed
+    // with a backslash, so the manifest can still be parsed line by line
+    let (prefix, name) = match needs_escape(name) {
+        true => ("\\", escape_name(name)),
+        false => ("", name.to_string()),
+    };
+
+    match format {
+        ChecksumFormat::Gnu => {
+            let mode = if binary { '*' } else { ' ' };
+            format!("{}{} {}{}", prefix, hex_digest, mode, name)
+        }
+        ChecksumFormat::Bsd => {
+            format!("{}{} ({}) = {}", prefix, tag, name, hex_digest)
+        }
+    }
+}
+
+// One line of a checksum manifest: the expected digest and the file it belongs to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChecksumEntry {
+    pub digest: Digest,
+    pub name: String,
+    pub format: ChecksumFormat,
+}
+
+// Result of re-hashing the file of a manifest entry
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    Failed,
+    // The file does not exist or could not be read
+    Missing,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "OK",
+            CheckStatus::Failed => "FAILED",
+            CheckStatus::Missing => "MISSING",
+        }
+    }
+}
+
+// Parses a GNU or BSD tagged line. The BSD format names its algorithm, the GNU format does not,
+// so `algorithm` is the one used for GNU lines (the `--algorithm` option of the CLI)
+pub fn parse_line(line: &str, algorithm: Algorithm) -> Result<ChecksumEntry, Error> {
+    let line = line.trim_end_matches(['\n', '\r']);
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+
+    let mut entry = match parse_bsd_line(line) {
+        Some(entry) => entry,
+        None => parse_gnu_line(line, algorithm)?,
+    };
+    if escaped {
+        entry.name = unescape_name(&entry.name)?;
+    }
+    Ok(entry)
+}
+
+// Hashes the file named by the entry with the algorithm of the entry and compares both digests.
+// Relative names are resolved from the current directory, as coreutils does
+pub fn check_entry(entry: &ChecksumEntry) -> CheckStatus {
+    match digest_file(entry.digest.algorithm(), Path::new(&entry.name)) {
+        Ok(digest) if digest == entry.digest => CheckStatus::Ok,
+        Ok(_) => CheckStatus::Failed,
+        Err(_) => CheckStatus::Missing,
+    }
+}
+
+// `SHA256 (name) = hex`
+fn parse_bsd_line(line: &str) -> Option<ChecksumEntry> {
+    let (tag, rest) = line.split_once(" (")?;
+    let algorithm = tag.parse::<Algorithm>().ok()?;
+    let (name, hex_digest) = rest.rsplit_once(") = ")?;
+    let digest = Digest::from_hex(algorithm, hex_digest).ok()?;
+    Some(ChecksumEntry {
+        digest,
+        name: name.to_string(),
+        format: ChecksumFormat::Bsd,
+    })
+}
+
+// `hex  name` or `hex *name`
+fn parse_gnu_line(line: &str, algorithm: Algorithm) -> Result<ChecksumEntry, Error> {
+    let (hex_digest, rest) = line.split_once(' ').ok_or(Error::InvalidChecksumLine)?;
+    let name = rest
+        .strip_prefix([' ', '*'])
+        .filter(|name| !name.is_empty())
+        .ok_or(Error::InvalidChecksumLine)?;
+    let digest = Digest::from_hex(algorithm, hex_digest).map_err(|_| Error::InvalidChecksumLine)?;
+    Ok(ChecksumEntry {
+        digest,
+        name: name.to_string(),
+        format: ChecksumFormat::Gnu,
+    })
+}
+
+fn unescape_name(name: &str) -> Result<String, Error> {
+    let mut unescaped = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\\') => unescaped.push('\\'),
+                Some('n') => unescaped.push('\n'),
+                Some('r') => unescaped.push('\r'),
+                _ => return Err(Error::InvalidChecksumLine),
+            },
+            _ => unescaped.push(c),
+        }
+    }
+    Ok(unescaped)
+}
+
+pub(crate) fn needs_escape(name: &str) -> bool {
+    name.contains(['\\', '\n', '\r'])
+}
+
+pub(crate) fn escape_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
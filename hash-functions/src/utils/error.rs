@@ -0,0 +1,133 @@
+use thiserror::Error;
+
+/// Possible errors thrown by the hash functions library.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The algorithm name does not match any supported hash function
+    #[error("Unknown hash algorithm: {0}")]
+    UnknownAlgorithm(String),
+
+    /// The digest bytes do not have the output length of the algorithm
+    #[error("Invalid digest length: expected {expected} bytes, got {actual}")]
+    InvalidDigestLength { expected: usize, actual: usize },
+
+    /// Hex string decoding error
+    #[error("Hex string decoding error")]
+    InvalidHexString,
+
+    /// The encoding name does not match any supported text encoding
+    #[error("Unknown encoding: {0}")]
+    UnknownEncoding(String),
+
+    /// The text is not in the canonical form of the encoding
+    #[error("Invalid {0} string")]
+    InvalidEncoding(&'static str),
+
+    /// The decoded bytes do not have the expected length
+    #[error("Invalid decoded length: expected {expected} bytes, got {actual}")]
+    InvalidDecodedLength { expected: usize, actual: usize },
+
+    /// The line is not in the GNU or BSD checksum format
+    #[error("Improperly formatted checksum line")]
+    InvalidChecksumLine,
+
+    /// The digest does not match the data
+    #[error("Digest verification failed")]
+    DigestMismatch,
+
+    /// No blob is stored at this address
+    #[error("Blob not found: {0}")]
+    BlobNotFound(String),
+
+    /// The proof-of-work stamp is malformed or does not meet the policy
+    #[error("Invalid stamp: {0}")]
+    InvalidStamp(String),
+
+    /// The proof-of-work stamp was already used
+    #[error("Stamp already spent")]
+    StampAlreadySpent,
+
+    /// The signature does not match the message and the public key
+    #[error("Invalid signature")]
+    InvalidSignature,
+
+    /// A one-time signing key was asked to sign a second time
+    #[error("One-time key already used")]
+    OneTimeKeyReused,
+
+    /// Every one-time key of a many-time signing key has been used
+    #[error("Signing key exhausted")]
+    SigningKeyExhausted,
+
+    /// Another process has the signing key file open
+    #[error("Key file in use: {0}")]
+    KeyFileInUse(String),
+
+    /// The filter has no room left for another item
+    #[error("Filter full")]
+    FilterFull,
+
+    /// The random bit generator produced the most outputs allowed between two reseeds
+    #[error("Reseed required")]
+    ReseedRequired,
+
+    /// The text is not a valid I-JSON document
+    #[error("Invalid JSON at byte {position}: {reason}")]
+    InvalidJson { position: usize, reason: &'static str },
+
+    /// The operation is not possible with this hash function
+    #[error("Not supported with {0}")]
+    UnsupportedAlgorithm(String),
+
+    /// A parameter is out of the range the operation accepts
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(String),
+
+    /// The search stopped before finding a result
+    #[error("Search exhausted after {evaluations} hash evaluations")]
+    SearchExhausted { evaluations: u64 },
+
+    /// The multicodec code does not match any supported hash function
+    #[error("Unknown multihash code: {0:#x}")]
+    UnknownMultihashCode(u64),
+
+    /// The bytes are not a valid multihash
+    #[error("Invalid multihash: {0}")]
+    InvalidMultihash(String),
+
+    /// The MAC tag does not match the message and key
+    #[error("MAC tag verification failed")]
+    InvalidMac,
+
+    /// The pseudorandom key is shorter than the hash output
+    #[error("Invalid pseudorandom key length: expected at least {expected} bytes, got {actual}")]
+    InvalidPrkLength { expected: usize, actual: usize },
+
+    /// The requested output is longer than the function can produce
+    #[error("Invalid output length: at most {max} bytes, got {actual}")]
+    InvalidOutputLength { max: usize, actual: usize },
+
+    /// The index does not exist in a collection of the given size
+    #[error("Index {index} is out of bounds for size {size}")]
+    IndexOutOfBounds { index: usize, size: usize },
+
+    /// The Merkle proof does not lead to the expected root
+    #[error("Invalid Merkle proof")]
+    InvalidMerkleProof,
+
+    /// The path cannot be represented in a manifest
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+
+    /// Error while reading the input to hash
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Error while walking a directory tree
+    #[error("Directory walk error: {0}")]
+    Walk(#[from] walkdir::Error),
+
+    /// The operating system random generator failed
+    #[error("Random generator error: {0}")]
+    Random(#[from] getrandom::Error),
+}
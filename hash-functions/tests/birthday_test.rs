@@ -0,0 +1,114 @@
+use std::process::Command;
+
+use hash_functions::attacks::birthday::{expected_evaluations, truncated_digest, Collision, CollisionSearch};
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::utils::error::Error;
+
+const BINARY: &str = env!("CARGO_BIN_EXE_hash-functions");
+
+fn assert_collides(collision: &Collision) {
+    assert_ne!(collision.first, collision.second);
+    for input in [&collision.first, &collision.second] {
+        assert_eq!(truncated_digest(collision.algorithm, collision.bits, input), collision.value);
+    }
+}
+
+#[test]
+fn collisions_on_truncated_digests() {
+    let cases = [Algorithm::Sha256, Algorithm::Sha3_256, Algorithm::Keccak256]
+        .into_iter()
+        .flat_map(|algorithm| [(algorithm, 16), (algorithm, 24)])
+        .chain([(Algorithm::Sha256, 32)]);
+    for (algorithm, bits) in cases {
+        let collision = CollisionSearch::new(algorithm, bits).unwrap().run().unwrap();
+
+        assert_collides(&collision);
+        assert_eq!(collision.algorithm, algorithm);
+        assert!(collision.value < 1 << bits);
+        // Far from the 2^bits of a brute force second preimage
+        assert!(collision.work_ratio() < 8.0, "{} {} bits: {:?}", algorithm, bits, collision);
+    }
+}
+
+#[test]
+fn work_matches_the_birthday_bound() {
+    let ratios: Vec<f64> = (0..20)
+        .map(|seed| {
+            let mut search = CollisionSearch::new(Algorithm::Sha256, 20).unwrap();
+            search.seed = seed;
+            search.run().unwrap().work_ratio()
+        })
+        .collect();
+    let mean = ratios.iter().sum::<f64>() / ratios.len() as f64;
+
+    // Chains overshoot the collision up to the next distinguished point, a bit more than 1
+    assert!((0.5..3.0).contains(&mean), "{:?}", ratios);
+    assert!((expected_evaluations(32) - 82137.5).abs() < 1.0);
+}
+
+#[test]
+fn bounded_table() {
+    let mut search = CollisionSearch::new(Algorithm::Sha256, 24).unwrap();
+    search.max_table_entries = 4;
+    let collision = search.run().unwrap();
+
+    assert_collides(&collision);
+    assert!(collision.table_entries <= 4);
+    assert!(collision.chains > 4);
+}
+
+#[test]
+fn seeds_replay_the_search() {
+    let run = |seed| {
+        let mut search = CollisionSearch::new(Algorithm::Sha256, 24).unwrap();
+        search.seed = seed;
+        search.run().unwrap()
+    };
+
+    assert_eq!(run(7).first, run(7).first);
+    assert_eq!(run(7).evaluations, run(7).evaluations);
+}
+
+#[test]
+fn invalid_parameters() {
+    for bits in [0, 7, 65, 256] {
+        assert!(matches!(CollisionSearch::new(Algorithm::Sha256, bits), Err(Error::InvalidParameter(_))));
+    }
+
+    let mut search = CollisionSearch::new(Algorithm::Sha256, 16).unwrap();
+    for distinguished_bits in [9, 16, 62, 64] {
+        search.distinguished_bits = distinguished_bits;
+        assert!(matches!(search.run(), Err(Error::InvalidParameter(_))));
+    }
+    let mut wide = CollisionSearch::new(Algorithm::Sha256, 64).unwrap();
+    wide.distinguished_bits = 62;
+    assert!(matches!(wide.run(), Err(Error::InvalidParameter(_))));
+
+    search.distinguished_bits = 4;
+    search.max_evaluations = 10;
+    assert!(matches!(search.run(), Err(Error::SearchExhausted { .. })));
+}
+
+#[test]
+fn collision_from_binary() {
+    let output = Command::new(BINARY)
+        .args(["birthday", "--bits", "24", "--algorithm", "sha3-256", "--seed", "3"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+    let first = hex::decode(lines.next().unwrap().strip_prefix("first: ").unwrap()).unwrap();
+    let second = hex::decode(lines.next().unwrap().strip_prefix("second: ").unwrap()).unwrap();
+    assert_ne!(first, second);
+    assert_eq!(
+        truncated_digest(Algorithm::Sha3_256, 24, &first),
+        truncated_digest(Algorithm::Sha3_256, 24, &second)
+    );
+    assert!(lines.next().unwrap().starts_with("SHA3-256 truncated to 24 bits: "));
+    assert!(lines.next().unwrap().contains("(expected 5134, ratio "));
+
+    let failure = Command::new(BINARY).args(["birthday", "--bits", "80"]).output().unwrap();
+    assert!(!failure.status.success());
+}
@@ -0,0 +1,118 @@
+use std::fs;
+use std::io::Cursor;
+
+use hash_functions::algorithms::hasher::digest;
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::structures::blob_store::{BlobStore, GcReport};
+use hash_functions::utils::error::Error;
+use tempfile::TempDir;
+
+const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+fn store(algorithm: Algorithm) -> (TempDir, BlobStore) {
+    let dir = TempDir::new().unwrap();
+    let store = BlobStore::open(dir.path().join("store"), algorithm).unwrap();
+    (dir, store)
+}
+
+#[test]
+fn put_and_get() {
+    let (_dir, store) = store(Algorithm::Sha256);
+    let address = store.put(b"hello").unwrap();
+
+    assert_eq!(address.to_hex(), HELLO_SHA256);
+    assert!(store.contains(&address));
+    assert_eq!(store.get(&address).unwrap(), b"hello");
+    // Fan-out layout
+    assert!(store.root().join("objects/sha-256/2c").join(&HELLO_SHA256[2..]).is_file());
+
+    // Same content, same blob
+    assert_eq!(store.put(b"hello").unwrap(), address);
+    assert_eq!(store.list().unwrap(), [address]);
+}
+
+#[test]
+fn put_reader_and_file() {
+    let (dir, store) = store(Algorithm::Sha3_256);
+    let big: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+
+    let address = store.put_reader(Cursor::new(&big)).unwrap();
+    assert_eq!(address, digest(Algorithm::Sha3_256, &big));
+    assert_eq!(store.get(&address).unwrap(), big);
+
+    let path = dir.path().join("input");
+    fs::write(&path, b"file content").unwrap();
+    let file_address = store.put_file(&path).unwrap();
+    assert_eq!(file_address, store.put(b"file content").unwrap());
+    assert_eq!(store.list().unwrap().len(), 2);
+    // Nothing is left in the temporary directory
+    assert_eq!(fs::read_dir(store.root().join("tmp")).unwrap().count(), 0);
+}
+
+#[test]
+fn damaged_blobs_are_detected() {
+    let (_dir, store) = store(Algorithm::Sha256);
+    let address = store.put(b"hello").unwrap();
+    fs::write(store.root().join("objects/sha-256/2c").join(&HELLO_SHA256[2..]), b"hellO").unwrap();
+
+    assert!(matches!(store.get(&address), Err(Error::DigestMismatch)));
+
+    let missing = digest(Algorithm::Sha256, b"never stored");
+    assert!(matches!(store.get(&missing), Err(Error::BlobNotFound(_))));
+    assert!(matches!(store.remove(&missing), Err(Error::BlobNotFound(_))));
+}
+
+#[test]
+fn blobs_of_several_algorithms() {
+    let (dir, sha2_store) = store(Algorithm::Sha256);
+    let truncated_store = BlobStore::open(dir.path().join("store"), Algorithm::Sha512_256).unwrap();
+
+    let sha2_address = sha2_store.put(b"data").unwrap();
+    let truncated_address = truncated_store.put(b"data").unwrap();
+
+    assert_ne!(sha2_address, truncated_address);
+    assert!(truncated_store.root().join("objects/sha-512-256").is_dir());
+    assert_eq!(truncated_store.get(&sha2_address).unwrap(), b"data");
+    assert_eq!(sha2_store.list().unwrap().len(), 2);
+}
+
+#[test]
+fn references() {
+    let (_dir, store) = store(Algorithm::Sha256);
+    let address = store.put(b"hello").unwrap();
+
+    store.set_ref("main", &address).unwrap();
+    assert_eq!(store.get_ref("main").unwrap(), Some(address.clone()));
+    assert_eq!(store.refs().unwrap(), [("main".to_string(), address.clone())]);
+
+    assert!(store.remove_ref("main").unwrap());
+    assert!(!store.remove_ref("main").unwrap());
+    assert_eq!(store.get_ref("main").unwrap(), None);
+
+    for name in ["", "../escape", "a/b", ".hidden"] {
+        assert!(matches!(store.set_ref(name, &address), Err(Error::InvalidPath(_))));
+    }
+    let missing = digest(Algorithm::Sha256, b"never stored");
+    assert!(matches!(store.set_ref("missing", &missing), Err(Error::BlobNotFound(_))));
+}
+
+#[test]
+fn garbage_collection() {
+    let (_dir, store) = store(Algorithm::Sha256);
+    let kept = store.put(b"kept").unwrap();
+    let dropped = store.put(b"dropped blob").unwrap();
+    store.set_ref("kept", &kept).unwrap();
+    fs::write(store.root().join("tmp/interrupted"), b"partial").unwrap();
+
+    let report = store.gc().unwrap();
+    assert_eq!(report, GcReport { kept: 1, removed: 1, freed_bytes: 12 });
+    assert!(store.contains(&kept) && !store.contains(&dropped));
+    assert_eq!(fs::read_dir(store.root().join("tmp")).unwrap().count(), 0);
+    // The fan-out directory of the removed blob is gone
+    let fan_out = &dropped.to_hex()[..2];
+    assert!(!store.root().join("objects/sha-256").join(fan_out).exists());
+
+    store.remove_ref("kept").unwrap();
+    assert_eq!(store.gc().unwrap().removed, 1);
+    assert!(store.list().unwrap().is_empty());
+}
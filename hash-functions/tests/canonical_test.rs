@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::elements::canonical::{json_digest, CanonicalEncoding, Value};
+use hash_functions::utils::error::Error;
+
+// RFC 8785 section 3.2.2
+const RFC_8785_INPUT: &str = r#"{
+  "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+  "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+  "literals": [null, true, false]
+}"#;
+const RFC_8785_OUTPUT: &str =
+    r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#;
+
+// RFC 8785 section 3.2.3, members sorted by UTF-16 code units: the emoji (D83D DE00) comes
+// before U+FB33 although its UTF-8 encoding sorts after
+const SORTING_INPUT: &str = r#"{
+  "\u20ac": "Euro Sign",
+  "\r": "Carriage Return",
+  "\ufb33": "Hebrew Letter Dalet With Dagesh",
+  "1": "One",
+  "\ud83d\ude00": "Emoji: Grinning Face",
+  "\u0080": "Control",
+  "\u00f6": "Latin Small Letter O With Diaeresis"
+}"#;
+const SORTING_KEYS: [&str; 7] = ["\r", "1", "\u{80}", "ö", "€", "😀", "\u{fb33}"];
+
+// RFC 8785 appendix B: IEEE 754 bits and their JSON form
+const NUMBERS: [(u64, &str); 16] = [
+    (0x0000000000000001, "5e-324"),
+    (0x8000000000000001, "-5e-324"),
+    (0x7fefffffffffffff, "1.7976931348623157e+308"),
+    (0xffefffffffffffff, "-1.7976931348623157e+308"),
+    (0x4340000000000000, "9007199254740992"),
+    (0xc340000000000000, "-9007199254740992"),
+    (0x4430000000000000, "295147905179352830000"),
+    (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+    (0x44b52d02c7e14af6, "1e+23"),
+    (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+    (0x444b1ae4d6e2ef50, "1e+21"),
+    (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+    (0x3eb0c6f7a0b5ed8d, "0.000001"),
+    (0x41b3de4355555554, "333333333.33333325"),
+    (0xbecbf647612f3696, "-0.0000033333333333333333"),
+    (0x43143ff3c1cb0959, "1424953923781206.2"),
+];
+
+// RFC 8949 appendix A, in hex
+fn cbor(value: Value) -> String {
+    hex::encode(value.to_cbor())
+}
+
+fn json(text: &str) -> Value {
+    Value::from_json(text).unwrap()
+}
+
+#[test]
+fn jcs_rfc_8785_examples() {
+    assert_eq!(json(RFC_8785_INPUT).to_jcs().unwrap(), RFC_8785_OUTPUT);
+
+    let sorted = json(SORTING_INPUT).to_jcs().unwrap();
+    let positions: Vec<usize> = SORTING_KEYS
+        .iter()
+        .map(|key| sorted.find(&format!("\"{}\":", key.replace('\r', "\\r"))).unwrap())
+        .collect();
+    assert!(positions.windows(2).all(|pair| pair[0] < pair[1]), "{}", sorted);
+
+    for (bits, expected) in NUMBERS {
+        assert_eq!(Value::Float(f64::from_bits(bits)).to_jcs().unwrap(), expected);
+        assert_eq!(json(expected).to_jcs().unwrap(), expected);
+    }
+    assert_eq!(Value::Float(-0.0).to_jcs().unwrap(), "0");
+
+    // Integers between 2^53 and 2^63 get the shortest digits of the double, padded with zeros
+    let integers = [
+        (1i64 << 60, "1152921504606847000"),
+        (-(1i64 << 60), "-1152921504606847000"),
+        ((1 << 53) + 2, "9007199254740994"),
+        (1 << 62, "4611686018427388000"),
+        (1_000_000_000_000_000_000, "1000000000000000000"),
+    ];
+    for (integer, expected) in integers {
+        assert_eq!(Value::Integer(integer).to_jcs().unwrap(), expected);
+        assert_eq!(Value::Float(integer as f64).to_jcs().unwrap(), expected);
+        assert_eq!(json(expected).to_jcs().unwrap(), expected);
+    }
+    assert_eq!(json("1152921504606846976").to_jcs().unwrap(), "1152921504606847000");
+}
+
+#[test]
+fn equal_objects_equal_digests() {
+    let documents = [
+        r#"{"user": "alice", "roles": ["admin", "dev"], "quota": 1.0e3, "active": true}"#,
+        r#"{"active":true,"quota":1000,"roles":["admin","dev"],"user":"alice"}"#,
+        "{\n  \"roles\" : [ \"admin\", \"d\\u0065v\" ],\n  \"quota\": 1000.0,\n  \"user\": \"alice\",\n  \"active\": true\n}",
+    ];
+    let value = json(documents[0]);
+    for encoding in [CanonicalEncoding::Jcs, CanonicalEncoding::Cbor] {
+        let expected = value.digest(Algorithm::Sha256, encoding).unwrap();
+        for document in documents {
+            assert_eq!(json(document).digest(Algorithm::Sha256, encoding).unwrap(), expected);
+        }
+    }
+    assert_eq!(json_digest(Algorithm::Sha3_256, documents[2]).unwrap(), json_digest(Algorithm::Sha3_256, documents[1]).unwrap());
+
+    // Another object, or the same object under the other encoding, is another digest
+    let other = json_digest(Algorithm::Sha256, r#"{"active":true,"quota":1001,"roles":["admin","dev"],"user":"alice"}"#);
+    assert_ne!(other.unwrap(), json_digest(Algorithm::Sha256, documents[0]).unwrap());
+    assert_ne!(
+        value.digest(Algorithm::Sha256, CanonicalEncoding::Jcs).unwrap(),
+        value.digest(Algorithm::Sha256, CanonicalEncoding::Cbor).unwrap()
+    );
+}
+
+#[test]
+fn numbers_have_one_form() {
+    assert_eq!(json("1.0"), Value::Integer(1));
+    assert_eq!(json("-0"), Value::Integer(0));
+    assert_eq!(json("1e2"), Value::Integer(100));
+    assert_eq!(json("0.5"), Value::Float(0.5));
+    // Beyond 2^53 a JSON number is the nearest double
+    assert_eq!(json("9007199254740993").to_jcs().unwrap(), "9007199254740992");
+
+    assert_eq!(Value::Float(100.0).to_cbor(), Value::Integer(100).to_cbor());
+    assert_eq!(Value::Float(1e18).to_jcs().unwrap(), Value::Integer(1_000_000_000_000_000_000).to_jcs().unwrap());
+    assert!(matches!(Value::Integer(i64::MAX).to_jcs(), Err(Error::InvalidParameter(_))));
+    assert!(Value::Float(f64::NAN).to_jcs().is_err());
+    assert!(Value::Bytes(vec![1]).to_jcs().is_err());
+}
+
+#[test]
+fn cbor_rfc_8949_examples() {
+    let integers = [
+        (0, "00"),
+        (23, "17"),
+        (24, "1818"),
+        (1000, "1903e8"),
+        (1_000_000, "1a000f4240"),
+        (1_000_000_000_000, "1b000000e8d4a51000"),
+        (-1, "20"),
+        (-100, "3863"),
+        (-1000, "3903e7"),
+    ];
+    for (integer, expected) in integers {
+        assert_eq!(cbor(Value::Integer(integer)), expected);
+    }
+
+    let floats = [
+        (1.1, "fb3ff199999999999a"),
+        (1.5, "f93e00"),
+        (5.960464477539063e-8, "f90001"),
+        (0.00006103515625, "f90400"),
+        (-4.1, "fbc010666666666666"),
+        (3.4028234663852886e+38, "fa7f7fffff"),
+        (1.0e+300, "fb7e37e43c8800759c"),
+        (f64::INFINITY, "f97c00"),
+        (f64::NEG_INFINITY, "f9fc00"),
+        (f64::NAN, "f97e00"),
+        (-4.0, "23"),
+    ];
+    for (float, expected) in floats {
+        assert_eq!(cbor(Value::Float(float)), expected, "{}", float);
+    }
+
+    assert_eq!(cbor(Value::from("IETF")), "6449455446");
+    assert_eq!(cbor(Value::from("水")), "63e6b0b4");
+    assert_eq!(cbor(Value::Bytes(vec![1, 2, 3, 4])), "4401020304");
+    assert_eq!(cbor(Value::Null), "f6");
+    assert_eq!(cbor(json("[1, [2, 3], [4, 5]]")), "8301820203820405");
+    assert_eq!(cbor(json(r#"{"b": [2, 3], "a": 1}"#)), "a26161016162820203");
+
+    // Shorter keys first, then byte order
+    let mut members = BTreeMap::new();
+    members.insert("aa".to_string(), Value::Integer(1));
+    members.insert("b".to_string(), Value::Integer(2));
+    assert_eq!(cbor(Value::Object(members)), "a261620262616101");
+}
+
+#[test]
+fn invalid_json() {
+    let documents = [
+        ("", 0),
+        ("{\"a\":1,\"a\":2}", 7),
+        ("[1,]", 3),
+        ("01", 1),
+        ("1.", 2),
+        ("\"\\ud800\"", 1),
+        ("\"tab\there\"", 4),
+        ("1e999", 0),
+        ("[1] x", 4),
+        ("tru", 0),
+    ];
+    for (document, position) in documents {
+        match Value::from_json(document) {
+            Err(Error::InvalidJson { position: found, .. }) => assert_eq!(found, position, "{:?}", document),
+            other => panic!("{:?} parsed as {:?}", document, other),
+        }
+    }
+
+    let deep = "[".repeat(200) + &"]".repeat(200);
+    assert!(Value::from_json(&deep).is_err());
+    let fine = "[".repeat(100) + &"]".repeat(100);
+    assert!(Value::from_json(&fine).is_ok());
+}
@@ -0,0 +1,393 @@
+// Reader and runners for the NIST CAVP response files (SHAVS for SHA-2, SHA3VS for SHA-3 and SHAKE,
+// DRBGVS for the random bit generators). A `.rsp` file is made of `[Name = value]` sections, each followed
+// by records of `Name = value` lines separated by blank lines. Lines starting with `#` are comments.
+// Apart from one SHA3-256 record, the SHA3VS runners have no NIST file to run yet, see `tests/vectors/cavp/README.md`
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use hash_functions::algorithms::drbg::{Drbg, Mechanism};
+use hash_functions::algorithms::hasher::{digest, Hasher};
+use hash_functions::algorithms::xof_algo::shake;
+use hash_functions::elements::algorithm::{Algorithm, XofAlgorithm};
+
+pub const VECTORS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/vectors/cavp");
+// The three DRBGVS archives use the same file names, they are kept in subdirectories of this one
+pub const DRBG_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/vectors/cavp/drbg");
+
+// Monte Carlo tests record a checkpoint every 1000 iterations, 100 times
+const MONTE_ITERATIONS: usize = 1000;
+const MONTE_CHECKPOINTS: usize = 100;
+// SHAKE Monte Carlo messages are the leftmost 128 bits of the previous output
+const SHAKE_MONTE_MESSAGE_LEN: usize = 16;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Function {
+    Fixed(Algorithm),
+    Xof(XofAlgorithm),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TestKind {
+    ShortMsg,
+    LongMsg,
+    Monte,
+    VariableOut,
+}
+
+// DRBGVS records repeat some names (`AdditionalInput`, `EntropyInputPR`), the fields keep their order
+#[derive(Debug, Default)]
+pub struct Record {
+    fields: Vec<(String, String)>,
+}
+
+#[derive(Debug, Default)]
+pub struct Section {
+    pub params: HashMap<String, String>,
+    pub records: Vec<Record>,
+}
+
+#[derive(Debug)]
+pub struct RspFile {
+    pub path: PathBuf,
+    pub function: Function,
+    pub kind: TestKind,
+    pub sections: Vec<Section>,
+}
+
+impl Record {
+    pub fn get(&self, name: &str) -> &str {
+        self.fields
+            .iter()
+            .find_map(|(field, value)| (field == name).then_some(value.as_str()))
+            .unwrap_or_else(|| panic!("missing field {} in {:?}", name, self.fields))
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.fields.iter().any(|(field, _)| field == name)
+    }
+
+    // Every value of a repeated field, in order
+    pub fn all_bytes(&self, name: &str) -> Vec<Vec<u8>> {
+        self.fields
+            .iter()
+            .filter(|(field, _)| field == name)
+            .map(|(_, value)| hex::decode(value).unwrap_or_else(|_| panic!("field {} is not hex", name)))
+            .collect()
+    }
+
+    pub fn bytes(&self, name: &str) -> Vec<u8> {
+        hex::decode(self.get(name)).unwrap_or_else(|_| panic!("field {} is not hex", name))
+    }
+
+    pub fn number(&self, name: &str) -> usize {
+        self.get(name).parse().unwrap_or_else(|_| panic!("field {} is not a number", name))
+    }
+
+    // `Msg` always has at least one byte, `Len = 0` is written as `Msg = 00`
+    pub fn message(&self) -> Vec<u8> {
+        let bits = self.number("Len");
+        assert_eq!(bits % 8, 0, "only byte oriented vectors are supported");
+        let mut message = self.bytes("Msg");
+        message.truncate(bits / 8);
+        message
+    }
+}
+
+impl Section {
+    pub fn param(&self, name: &str) -> usize {
+        self.params
+            .get(name)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_else(|| panic!("missing section parameter {} in {:?}", name, self.params))
+    }
+}
+
+impl RspFile {
+    pub fn open(path: &Path) -> RspFile {
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let (function, kind) = classify(name).unwrap_or_else(|| panic!("unknown CAVP file {}", name));
+        let content = fs::read_to_string(path).unwrap();
+
+        RspFile {
+            path: path.to_path_buf(),
+            function,
+            kind,
+            sections: parse(&content),
+        }
+    }
+
+    pub fn record_count(&self) -> usize {
+        self.sections.iter().map(|section| section.records.len()).sum()
+    }
+
+    // Checks every record of the file and returns how many were checked
+    pub fn run(&self) -> usize {
+        for section in &self.sections {
+            match (self.function, self.kind) {
+                (Function::Fixed(algorithm), TestKind::Monte) => fixed_monte(self, algorithm, section),
+                (Function::Xof(algorithm), TestKind::Monte) => shake_monte(self, algorithm, section),
+                (Function::Fixed(algorithm), _) => {
+                    for record in &section.records {
+                        check_fixed(self, algorithm, record);
+                    }
+                }
+                (Function::Xof(algorithm), _) => {
+                    for record in &section.records {
+                        check_shake(self, algorithm, section, record);
+                    }
+                }
+            }
+        }
+        self.record_count()
+    }
+}
+
+// All the `.rsp` files of the vectors directory, sorted by name
+pub fn vector_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(VECTORS_DIR)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "rsp"))
+        .collect();
+    files.sort();
+    files
+}
+
+// File names of the NIST archives: `SHA256ShortMsg`, `SHA512_256Monte`, `SHA3_384LongMsg`, `SHAKE128VariableOut`...
+pub fn classify(name: &str) -> Option<(Function, TestKind)> {
+    let kinds = [
+        ("ShortMsg", TestKind::ShortMsg),
+        ("LongMsg", TestKind::LongMsg),
+        ("Monte", TestKind::Monte),
+        ("VariableOut", TestKind::VariableOut),
+    ];
+    let (prefix, kind) = kinds
+        .into_iter()
+        .find_map(|(suffix, kind)| name.strip_suffix(suffix).map(|prefix| (prefix, kind)))?;
+
+    let function = match prefix {
+        "SHAKE128" => Function::Xof(XofAlgorithm::Shake128),
+        "SHAKE256" => Function::Xof(XofAlgorithm::Shake256),
+        "SHA512_256" => Function::Fixed(Algorithm::Sha512_256),
+        _ => Function::Fixed(prefix.parse().ok()?),
+    };
+    if kind == TestKind::VariableOut && matches!(function, Function::Fixed(_)) {
+        return None;
+    }
+    Some((function, kind))
+}
+
+pub fn parse(content: &str) -> Vec<Section> {
+    let mut sections = Vec::new();
+    let mut section = Section::default();
+    let mut record = Record::default();
+
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.is_empty() {
+            if !record.fields.is_empty() {
+                section.records.push(std::mem::take(&mut record));
+            }
+            continue;
+        }
+        if let Some(param) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            // A header right after records starts a new section, consecutive headers belong to the same one
+            if !record.fields.is_empty() {
+                section.records.push(std::mem::take(&mut record));
+            }
+            if !section.records.is_empty() {
+                sections.push(std::mem::take(&mut section));
+            }
+            let (name, value) = split_field(param);
+            section.params.insert(name, value);
+            continue;
+        }
+        record.fields.push(split_field(line));
+    }
+
+    if !record.fields.is_empty() {
+        section.records.push(record);
+    }
+    if !section.records.is_empty() || !section.params.is_empty() {
+        sections.push(section);
+    }
+    sections
+}
+
+fn split_field(line: &str) -> (String, String) {
+    match line.split_once('=') {
+        Some((name, value)) => (name.trim().to_string(), value.trim().to_string()),
+        None => (line.to_string(), String::new()),
+    }
+}
+
+// Short and long messages: one-shot digest and the incremental hasher fed in uneven pieces
+fn check_fixed(file: &RspFile, algorithm: Algorithm, record: &Record) {
+    let message = record.message();
+    let expected = record.bytes("MD");
+
+    assert_eq!(
+        digest(algorithm, &message).as_bytes(),
+        expected,
+        "{} Len = {}",
+        file.path.display(),
+        message.len() * 8
+    );
+
+    let mut hasher = Hasher::new(algorithm);
+    for chunk in message.chunks(algorithm.output_len() + 7) {
+        hasher.update(chunk);
+    }
+    assert_eq!(hasher.finalize().as_bytes(), expected, "{} incremental", file.path.display());
+}
+
+// Short and long messages use the `Outputlen` of the section, variable output tests the one of each record
+fn check_shake(file: &RspFile, algorithm: XofAlgorithm, section: &Section, record: &Record) {
+    let (message, output_bits) = match file.kind {
+        TestKind::VariableOut => {
+            // The input length is a section parameter of these files
+            let mut message = record.bytes("Msg");
+            message.truncate(section.param("Input Length") / 8);
+            (message, record.number("Outputlen"))
+        }
+        _ => (record.message(), section.param("Outputlen")),
+    };
+    let expected = record.bytes("Output");
+
+    assert_eq!(output_bits, expected.len() * 8, "{}", file.path.display());
+    assert_eq!(
+        shake(algorithm, &message, expected.len()),
+        expected,
+        "{} Msg = {}",
+        file.path.display(),
+        hex::encode(&message)
+    );
+}
+
+// SHAVS 6.4 for SHA-2: every digest is taken over the last three digests.
+// SHA3VS 6.2.3 for SHA-3: every digest is taken over the previous one
+fn fixed_monte(file: &RspFile, algorithm: Algorithm, section: &Section) {
+    let mut records = section.records.iter();
+    let mut seed = records.next().expect("seed record").bytes("Seed");
+    let checkpoints: Vec<&Record> = records.collect();
+    assert_eq!(checkpoints.len(), MONTE_CHECKPOINTS, "{}", file.path.display());
+
+    let sha3 = matches!(
+        algorithm,
+        Algorithm::Sha3_224 | Algorithm::Sha3_256 | Algorithm::Sha3_384 | Algorithm::Sha3_512
+    );
+    for (count, checkpoint) in checkpoints.iter().enumerate() {
+        assert_eq!(checkpoint.number("COUNT"), count);
+        seed = match sha3 {
+            true => (0..MONTE_ITERATIONS).fold(seed, |md, _| digest(algorithm, &md).into_bytes()),
+            false => {
+                let mut last = [seed.clone(), seed.clone(), seed];
+                for _ in 0..MONTE_ITERATIONS {
+                    let md = digest(algorithm, &last.concat()).into_bytes();
+                    last = [last[1].clone(), last[2].clone(), md];
+                }
+                let [_, _, md] = last;
+                md
+            }
+        };
+        assert_eq!(seed, checkpoint.bytes("MD"), "{} COUNT = {}", file.path.display(), count);
+    }
+}
+
+// SHA3VS 6.3.3: the message is the leftmost 128 bits of the previous output and the length of the next
+// output comes from its rightmost 16 bits
+fn shake_monte(file: &RspFile, algorithm: XofAlgorithm, section: &Section) {
+    let min_len = section.param("Minimum Output Length (bits)") / 8;
+    let max_len = section.param("Maximum Output Length (bits)") / 8;
+    let range = max_len - min_len + 1;
+
+    let mut records = section.records.iter();
+    let mut output = records.next().expect("message record").bytes("Msg");
+    let checkpoints: Vec<&Record> = records.collect();
+    assert_eq!(checkpoints.len(), MONTE_CHECKPOINTS, "{}", file.path.display());
+
+    let mut output_len = max_len;
+    for (count, checkpoint) in checkpoints.iter().enumerate() {
+        assert_eq!(checkpoint.number("COUNT"), count);
+        for _ in 0..MONTE_ITERATIONS {
+            let mut message = output[..output.len().min(SHAKE_MONTE_MESSAGE_LEN)].to_vec();
+            message.resize(SHAKE_MONTE_MESSAGE_LEN, 0);
+            output = shake(algorithm, &message, output_len);
+            let rightmost = u16::from_be_bytes([output[output.len() - 2], output[output.len() - 1]]);
+            output_len = min_len + rightmost as usize % range;
+        }
+        assert_eq!(output, checkpoint.bytes("Output"), "{} COUNT = {}", file.path.display(), count);
+        assert_eq!(output.len() * 8, checkpoint.number("Outputlen"));
+    }
+}
+
+// `HMAC_DRBG.rsp` and `Hash_DRBG.rsp` of every subdirectory of the DRBG directory
+pub fn drbg_files() -> Vec<(Mechanism, PathBuf)> {
+    let mut files = Vec::new();
+    let mut directories = vec![PathBuf::from(DRBG_DIR)];
+    while let Some(directory) = directories.pop() {
+        let Ok(entries) = fs::read_dir(&directory) else { continue };
+        for path in entries.map(|entry| entry.unwrap().path()) {
+            match path.file_name().and_then(|name| name.to_str()) {
+                _ if path.is_dir() => directories.push(path),
+                Some("HMAC_DRBG.rsp") => files.push((Mechanism::HmacDrbg, path)),
+                Some("Hash_DRBG.rsp") => files.push((Mechanism::HashDrbg, path)),
+                _ => {}
+            }
+        }
+    }
+    files.sort_by(|(_, a), (_, b)| a.cmp(b));
+    files
+}
+
+// DRBGVS: instantiate, reseed when the record has a reseed entropy input, generate twice and compare
+// the second output. With prediction resistance each generate takes its own entropy input.
+// Sections of SHA-1 and SHA-512/224 are skipped, returns the number of records checked
+pub fn run_drbg(name: &str, mechanism: Mechanism, content: &str) -> usize {
+    let mut checked = 0;
+    for section in parse(content) {
+        let Some(algorithm) = section.params.keys().find_map(|param| param.parse::<Algorithm>().ok()) else {
+            continue;
+        };
+        let returned_len = section.param("ReturnedBitsLen") / 8;
+        for record in &section.records {
+            let mut drbg = Drbg::new(
+                mechanism,
+                algorithm,
+                &record.bytes("EntropyInput"),
+                &record.bytes("Nonce"),
+                &record.bytes("PersonalizationString"),
+            )
+            .unwrap();
+            if record.has("EntropyInputReseed") {
+                drbg.reseed(&record.bytes("EntropyInputReseed"), &record.bytes("AdditionalInputReseed"))
+                    .unwrap();
+            }
+
+            let additional_inputs = record.all_bytes("AdditionalInput");
+            let prediction_entropy = record.all_bytes("EntropyInputPR");
+            let mut output = vec![0u8; returned_len];
+            for (i, additional_input) in additional_inputs.iter().enumerate() {
+                match prediction_entropy.get(i) {
+                    Some(entropy) => drbg.generate_with_prediction_resistance(entropy, &mut output, additional_input),
+                    None => drbg.generate(&mut output, additional_input),
+                }
+                .unwrap();
+            }
+            assert_eq!(
+                output,
+                record.bytes("ReturnedBits"),
+                "{} {} {} COUNT = {}",
+                name,
+                mechanism,
+                algorithm,
+                record.get("COUNT")
+            );
+            checked += 1;
+        }
+    }
+    checked
+}
@@ -0,0 +1,158 @@
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use hash_functions::algorithms::hasher::digest;
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::utils::checksum::{check_entry, format_line, parse_line, CheckStatus, ChecksumFormat};
+
+const BINARY: &str = env!("CARGO_BIN_EXE_hash-functions");
+const MESSAGE: &[u8; 3] = b"abc";
+const SHA256_ABC: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+#[test]
+fn gnu_and_bsd_lines() {
+    let hashed = digest(Algorithm::Sha256, MESSAGE);
+
+    assert_eq!(
+        format_line(&hashed, "abc.txt", ChecksumFormat::Gnu, false),
+        format!("{}  abc.txt", SHA256_ABC)
+    );
+    assert_eq!(
+        format_line(&hashed, "abc.txt", ChecksumFormat::Gnu, true),
+        format!("{} *abc.txt", SHA256_ABC)
+    );
+    assert_eq!(
+        format_line(&hashed, "abc.txt", ChecksumFormat::Bsd, false),
+        format!("SHA256 (abc.txt) = {}", SHA256_ABC)
+    );
+    assert_eq!(
+        format_line(&digest(Algorithm::Sha3_256, MESSAGE), "-", ChecksumFormat::Bsd, false),
+        "SHA3-256 (-) = 3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+    );
+}
+
+#[test]
+fn escape_special_names() {
+    let hashed = digest(Algorithm::Sha256, MESSAGE);
+
+    assert_eq!(
+        format_line(&hashed, "a\\b\nc", ChecksumFormat::Gnu, false),
+        format!("\\{}  a\\\\b\\nc", SHA256_ABC)
+    );
+}
+
+#[test]
+fn hash_stdin_from_binary() {
+    let mut child = Command::new(BINARY)
+        .args(["--algorithm", "sha256"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(MESSAGE).unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{}  -\n", SHA256_ABC)
+    );
+}
+
+#[test]
+fn missing_file_fails() {
+    let output = Command::new(BINARY)
+        .arg("./does/not/exist")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn parse_gnu_and_bsd_lines() {
+    let gnu = parse_line(&format!("{} *abc.txt", SHA256_ABC), Algorithm::Sha256).unwrap();
+    assert_eq!(gnu.digest, digest(Algorithm::Sha256, MESSAGE));
+    assert_eq!(gnu.name, "abc.txt");
+    assert_eq!(gnu.format, ChecksumFormat::Gnu);
+
+    // The BSD tag wins over the default algorithm
+    let bsd_line = format_line(&digest(Algorithm::Sha3_224, MESSAGE), "a (1).txt", ChecksumFormat::Bsd, false);
+    let bsd = parse_line(&bsd_line, Algorithm::Sha256).unwrap();
+    assert_eq!(bsd.digest, digest(Algorithm::Sha3_224, MESSAGE));
+    assert_eq!(bsd.name, "a (1).txt");
+
+    let escaped_line = format_line(&digest(Algorithm::Sha256, MESSAGE), "a\\b\nc", ChecksumFormat::Gnu, false);
+    assert_eq!(parse_line(&escaped_line, Algorithm::Sha256).unwrap().name, "a\\b\nc");
+
+    assert!(parse_line(&format!("{} abc.txt", SHA256_ABC), Algorithm::Sha256).is_err());
+    assert!(parse_line(&format!("{}  abc.txt", SHA256_ABC), Algorithm::Sha512).is_err());
+    assert!(parse_line("not a checksum line", Algorithm::Sha256).is_err());
+}
+
+#[test]
+fn check_entries_against_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("abc.txt");
+    fs::write(&path, MESSAGE).unwrap();
+    let name = path.to_string_lossy();
+
+    let good = parse_line(&format!("{}  {}", SHA256_ABC, name), Algorithm::Sha256).unwrap();
+    let bad = parse_line(&format!("{}  {}", "00".repeat(32), name), Algorithm::Sha256).unwrap();
+    let missing = parse_line(&format!("{}  {}/nope", SHA256_ABC, dir.path().display()), Algorithm::Sha256).unwrap();
+
+    assert_eq!(check_entry(&good), CheckStatus::Ok);
+    assert_eq!(check_entry(&bad), CheckStatus::Failed);
+    assert_eq!(check_entry(&missing), CheckStatus::Missing);
+}
+
+#[test]
+fn check_manifest_from_binary() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("abc.txt"), MESSAGE).unwrap();
+    fs::write(dir.path().join("changed.txt"), b"abd").unwrap();
+    let manifest = [
+        format!("{}  abc.txt", SHA256_ABC),
+        format_line(&digest(Algorithm::Sha3_512, MESSAGE), "abc.txt", ChecksumFormat::Bsd, false),
+        format!("{}  changed.txt", SHA256_ABC),
+        format!("{}  missing.txt", SHA256_ABC),
+    ].join("\n");
+    fs::write(dir.path().join("SHA256SUMS"), manifest).unwrap();
+
+    let output = Command::new(BINARY)
+        .args(["--check", "SHA256SUMS"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "abc.txt: OK\nabc.txt: OK\nchanged.txt: FAILED\nmissing.txt: MISSING\n"
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn directory_with_link_loop_from_binary() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("sub/abc.txt"), MESSAGE).unwrap();
+    std::os::unix::fs::symlink("..", dir.path().join("sub/parent")).unwrap();
+    std::os::unix::fs::symlink("abc.txt", dir.path().join("sub/link.txt")).unwrap();
+
+    let output = Command::new(BINARY)
+        .args(["--algorithm", "sha256", "sub"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+
+    // The loop is skipped, the link to a file is hashed
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{}  sub/abc.txt\n{}  sub/link.txt\n", SHA256_ABC, SHA256_ABC)
+    );
+}
@@ -0,0 +1,136 @@
+use std::collections::HashSet;
+use std::io::{self, Read};
+
+use hash_functions::algorithms::hasher::digest;
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::structures::blob_store::BlobStore;
+use hash_functions::structures::chunker::{chunk_bytes, Chunk, Chunker, ChunkerConfig};
+use hash_functions::utils::error::Error;
+use tempfile::TempDir;
+
+// Incompressible test data: SHA-256 in counter mode
+fn random_bytes(seed: u8, len: usize) -> Vec<u8> {
+    (0..len.div_ceil(32) as u64)
+        .flat_map(|counter| digest(Algorithm::Sha256, &[&[seed][..], &counter.to_be_bytes()].concat()).into_bytes())
+        .take(len)
+        .collect()
+}
+
+// Returns a few bytes per read, as sockets and pipes do
+struct SlowReader<'a>(&'a [u8]);
+
+impl Read for SlowReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.0.len()).min(1000);
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        Ok(n)
+    }
+}
+
+fn digests(chunks: &[Chunk]) -> HashSet<Vec<u8>> {
+    chunks.iter().map(|chunk| chunk.digest.as_bytes().to_vec()).collect()
+}
+
+#[test]
+fn chunks_cover_the_input() {
+    let data = random_bytes(1, 1 << 20);
+    let config = ChunkerConfig::default();
+    let chunks = chunk_bytes(Algorithm::Sha256, config, &data);
+
+    assert_eq!(chunks.iter().flat_map(|chunk| chunk.data.clone()).collect::<Vec<u8>>(), data);
+    let mut offset = 0;
+    for (i, chunk) in chunks.iter().enumerate() {
+        assert_eq!(chunk.offset, offset);
+        assert_eq!(chunk.digest, digest(Algorithm::Sha256, &chunk.data));
+        assert!(chunk.data.len() <= config.max_size());
+        assert!(chunk.data.len() >= config.min_size() || i == chunks.len() - 1);
+        offset += chunk.data.len() as u64;
+    }
+
+    // Sizes are spread around the average
+    let mean = data.len() / chunks.len();
+    assert!((config.avg_size() / 2..config.avg_size() * 2).contains(&mean), "mean chunk size {}", mean);
+}
+
+#[test]
+fn streaming_matches_buffer() {
+    let data = random_bytes(2, 300_000);
+    let config = ChunkerConfig::new(512, 2048, 8192).unwrap();
+
+    let streamed: Vec<Chunk> = Chunker::new(SlowReader(&data), Algorithm::Sha3_256, config)
+        .collect::<Result<_, Error>>()
+        .unwrap();
+    assert_eq!(streamed, chunk_bytes(Algorithm::Sha3_256, config, &data));
+    assert_eq!(
+        config.boundaries(&data),
+        streamed.iter().map(|chunk| chunk.offset as usize + chunk.data.len()).collect::<Vec<usize>>()
+    );
+}
+
+#[test]
+fn small_inputs() {
+    let config = ChunkerConfig::default();
+    assert!(chunk_bytes(Algorithm::Sha256, config, b"").is_empty());
+    assert_eq!(Chunker::new(&b""[..], Algorithm::Sha256, config).count(), 0);
+
+    let chunks = chunk_bytes(Algorithm::Sha256, config, b"short");
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].data, b"short");
+
+    // Without any boundary in the content, chunks have the maximum size
+    let zeros = vec![0u8; 3 * config.max_size() + 10];
+    let sizes: Vec<usize> = chunk_bytes(Algorithm::Sha256, config, &zeros).iter().map(|c| c.data.len()).collect();
+    assert_eq!(sizes[..3], [config.max_size(); 3]);
+}
+
+#[test]
+fn edits_in_the_middle_keep_most_chunks() {
+    let original = random_bytes(3, 1 << 20);
+    let mut edited = original.clone();
+    edited.splice(500_000..500_000, random_bytes(4, 100));
+    edited.drain(800_000..800_050);
+
+    let config = ChunkerConfig::default();
+    let before = chunk_bytes(Algorithm::Sha256, config, &original);
+    let after = chunk_bytes(Algorithm::Sha256, config, &edited);
+    let shared = digests(&before).intersection(&digests(&after)).count();
+
+    // Each edit changes one or two chunks around it
+    assert!(shared >= before.len() - 4, "{} of {} chunks shared", shared, before.len());
+
+    // Fixed-size blocks would lose everything after the insertion
+    let blocks = |data: &[u8]| -> HashSet<Vec<u8>> { data.chunks(8192).map(|block| block.to_vec()).collect() };
+    assert!(blocks(&original).intersection(&blocks(&edited)).count() <= 500_000 / 8192);
+}
+
+#[test]
+fn deduplicated_backup() {
+    let dir = TempDir::new().unwrap();
+    let store = BlobStore::open(dir.path(), Algorithm::Sha256).unwrap();
+    let original = random_bytes(5, 1 << 20);
+    let mut edited = original.clone();
+    edited[600_000] ^= 0xff;
+
+    let mut recipes = Vec::new();
+    for version in [&original, &edited] {
+        let mut recipe = Vec::new();
+        for chunk in Chunker::new(version.as_slice(), Algorithm::Sha256, ChunkerConfig::default()) {
+            recipe.push(store.put(&chunk.unwrap().data).unwrap());
+        }
+        recipes.push(recipe);
+    }
+
+    let restored: Vec<u8> = recipes[1].iter().flat_map(|address| store.get(address).unwrap()).collect();
+    assert_eq!(restored, edited);
+    // The second version only added the chunk holding the modified byte
+    assert_eq!(store.list().unwrap().len(), recipes[0].len() + 1);
+}
+
+#[test]
+fn invalid_sizes() {
+    for (min, avg, max) in [(0, 8192, 65536), (4096, 2048, 65536), (2048, 8192, 4096), (2048, 8192, 1 << 30)] {
+        assert!(matches!(ChunkerConfig::new(min, avg, max), Err(Error::InvalidParameter(_))));
+    }
+    assert!(ChunkerConfig::new(64, 64, 64).is_ok());
+}
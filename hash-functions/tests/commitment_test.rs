@@ -0,0 +1,94 @@
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::protocols::commitment::{Commitment, Opening, NONCE_LEN};
+use hash_functions::utils::error::Error;
+
+const DOMAIN: &str = "coin-flip";
+
+fn nonce() -> [u8; NONCE_LEN] {
+    std::array::from_fn(|i| i as u8)
+}
+
+// SHA-256 of 9 (u64 BE) || "coin-flip" || 00 01 .. 1f || 01, computed with Python hashlib
+const EXPECTED_DIGEST: &str = "6dcaf120b7dc0e7949eef89f8e28e2aa4138951222641ffdc10b6dc6fa1a4f47";
+
+#[test]
+fn commit_and_reveal() {
+    let (commitment, opening) = Commitment::commit_with_nonce(Algorithm::Sha256, DOMAIN, &[1], nonce()).unwrap();
+    assert_eq!(commitment.digest().to_hex(), EXPECTED_DIGEST);
+    assert_eq!(commitment.open(&opening).unwrap(), [1]);
+
+    for algorithm in Algorithm::ALL {
+        let (commitment, opening) = Commitment::commit(algorithm, "auction-42", b"1500 EUR").unwrap();
+        assert_eq!(commitment.algorithm(), algorithm);
+        assert!(commitment.verify(&opening).is_ok());
+    }
+}
+
+#[test]
+fn binding() {
+    let (commitment, opening) = Commitment::commit(Algorithm::Sha3_256, DOMAIN, b"heads").unwrap();
+
+    let other_value = Opening::new(b"tails".to_vec(), *opening.nonce());
+    assert!(matches!(commitment.verify(&other_value), Err(Error::DigestMismatch)));
+
+    let mut nonce = *opening.nonce();
+    nonce[0] ^= 1;
+    assert!(commitment.verify(&Opening::new(b"heads".to_vec(), nonce)).is_err());
+
+    // Same value and nonce committed for another use
+    let (other_domain, _) =
+        Commitment::commit_with_nonce(Algorithm::Sha3_256, "auction", b"heads", *opening.nonce()).unwrap();
+    assert_ne!(other_domain.digest(), commitment.digest());
+    let moved: Commitment = format!("auction:{}", commitment.to_string().split_once(':').unwrap().1).parse().unwrap();
+    assert!(moved.verify(&opening).is_err());
+}
+
+#[test]
+fn hiding() {
+    let (first, _) = Commitment::commit(Algorithm::Sha256, DOMAIN, b"heads").unwrap();
+    let (second, _) = Commitment::commit(Algorithm::Sha256, DOMAIN, b"heads").unwrap();
+    assert_ne!(first, second);
+}
+
+#[test]
+fn text_format() {
+    let (commitment, opening) = Commitment::commit_with_nonce(Algorithm::Sha256, DOMAIN, &[1], nonce()).unwrap();
+    let text = commitment.to_string();
+    assert_eq!(text, format!("coin-flip:1220{}", EXPECTED_DIGEST));
+
+    let parsed: Commitment = text.parse().unwrap();
+    assert_eq!(parsed, commitment);
+    let revealed: Opening = opening.to_string().parse().unwrap();
+    assert!(revealed == opening);
+    assert!(parsed.verify(&revealed).is_ok());
+    assert!(!format!("{:?}", opening).contains("0001"));
+
+    for invalid in ["", "coin-flip", ":1220aa", "coin flip:1220aa", "coin-flip:zz", "coin-flip:"] {
+        assert!(invalid.parse::<Commitment>().is_err(), "{}", invalid);
+    }
+    for invalid in ["", "0001", "0001:AQ", &format!("{}:A=", hex::encode(nonce()))] {
+        assert!(invalid.parse::<Opening>().is_err(), "{}", invalid);
+    }
+    assert!(matches!(
+        Commitment::commit(Algorithm::Sha256, "a:b", b""),
+        Err(Error::InvalidParameter(_))
+    ));
+}
+
+// Alice and Bob both commit to a random bit, exchange the commitments, then reveal.
+// Neither can choose their bit after seeing the other one
+#[test]
+fn fair_coin_flip() {
+    let (alice_commitment, alice_opening) = Commitment::commit(Algorithm::Sha256, DOMAIN, &[1]).unwrap();
+    let (bob_commitment, bob_opening) = Commitment::commit(Algorithm::Sha256, DOMAIN, &[0]).unwrap();
+
+    let alice_published: Commitment = alice_commitment.to_string().parse().unwrap();
+    let bob_published: Commitment = bob_commitment.to_string().parse().unwrap();
+
+    let alice_bit = alice_published.open(&alice_opening).unwrap()[0];
+    let bob_bit = bob_published.open(&bob_opening).unwrap()[0];
+    assert_eq!(alice_bit ^ bob_bit, 1);
+
+    // Bob cannot change his mind after seeing Alice's bit
+    assert!(bob_published.verify(&Opening::new(vec![1], *bob_opening.nonce())).is_err());
+}
@@ -0,0 +1,125 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use hash_functions::algorithms::hasher::digest;
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::structures::directory_manifest::DirectoryManifest;
+
+const BINARY: &str = env!("CARGO_BIN_EXE_hash-functions");
+
+fn release_tree(root: &Path) {
+    fs::create_dir_all(root.join("docs/api")).unwrap();
+    fs::create_dir_all(root.join("empty")).unwrap();
+    fs::write(root.join("readme.md"), b"# Release\n").unwrap();
+    fs::write(root.join("docs.txt"), b"see docs/").unwrap();
+    fs::write(root.join("docs/index.html"), b"<html></html>").unwrap();
+    fs::write(root.join("docs/api/hash.html"), b"").unwrap();
+    for i in 0..50 {
+        fs::write(root.join(format!("docs/api/page-{:02}.html", i)), vec![i as u8; i * 100]).unwrap();
+    }
+}
+
+#[test]
+fn entries_are_sorted_by_full_path() {
+    let dir = tempfile::tempdir().unwrap();
+    release_tree(dir.path());
+    let manifest = DirectoryManifest::build(Algorithm::Sha256, dir.path()).unwrap();
+
+    let paths: Vec<&str> = manifest.entries().iter().map(|entry| entry.path.as_str()).collect();
+    let mut sorted = paths.clone();
+    sorted.sort();
+    assert_eq!(paths, sorted);
+    assert_eq!(manifest.len(), 54);
+    // `.` sorts before `/`, the empty directory is not listed
+    assert_eq!(&paths[..3], ["docs.txt", "docs/api/hash.html", "docs/api/page-00.html"]);
+
+    let page = manifest.get("docs/api/page-07.html").unwrap();
+    assert_eq!(page.size, 700);
+    assert_eq!(page.digest, digest(Algorithm::Sha256, &[7; 700]));
+    assert_eq!(
+        manifest.to_string().lines().last().unwrap(),
+        format!("{}  10  readme.md", digest(Algorithm::Sha256, b"# Release\n"))
+    );
+}
+
+#[test]
+fn root_digest_is_deterministic_and_covers_the_tree() {
+    let (first, second) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    release_tree(first.path());
+    release_tree(second.path());
+    let root = DirectoryManifest::build(Algorithm::Sha3_256, first.path()).unwrap().root_digest();
+
+    assert_eq!(root.algorithm(), Algorithm::Sha3_256);
+    assert_eq!(DirectoryManifest::build(Algorithm::Sha3_256, second.path()).unwrap().root_digest(), root);
+
+    fs::write(second.path().join("docs/api/page-10.html"), b"changed").unwrap();
+    let modified = DirectoryManifest::build(Algorithm::Sha3_256, second.path()).unwrap().root_digest();
+    assert_ne!(modified, root);
+
+    fs::remove_file(second.path().join("docs/api/page-10.html")).unwrap();
+    let removed = DirectoryManifest::build(Algorithm::Sha3_256, second.path()).unwrap().root_digest();
+    assert_ne!(removed, root);
+
+    fs::write(second.path().join("docs/api/page-11.html"), b"").unwrap();
+    fs::rename(second.path().join("docs/api/page-11.html"), second.path().join("docs/api/page-10.html")).unwrap();
+    let renamed = DirectoryManifest::build(Algorithm::Sha3_256, second.path()).unwrap().root_digest();
+    assert_ne!(renamed, root);
+}
+
+#[test]
+fn single_file_is_proven_against_the_root() {
+    let dir = tempfile::tempdir().unwrap();
+    release_tree(dir.path());
+    let manifest = DirectoryManifest::build(Algorithm::Sha256, dir.path()).unwrap();
+    let root = manifest.root_digest();
+    let index = manifest.entries().iter().position(|entry| entry.path == "docs/index.html").unwrap();
+    let proof = manifest.merkle_tree().inclusion_proof(index).unwrap();
+
+    assert!(proof.verify(&manifest.entries()[index].leaf(), &root).is_ok());
+    assert!(proof.verify(&manifest.entries()[index + 1].leaf(), &root).is_err());
+}
+
+#[test]
+fn only_directories_are_accepted() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("file"), b"").unwrap();
+
+    assert!(DirectoryManifest::build(Algorithm::Sha256, dir.path().join("file")).is_err());
+    assert!(DirectoryManifest::build(Algorithm::Sha256, dir.path().join("missing")).is_err());
+    assert!(DirectoryManifest::build(Algorithm::Sha256, dir.path()).unwrap().get("file").is_some());
+}
+
+#[test]
+fn manifest_from_binary() {
+    let dir = tempfile::tempdir().unwrap();
+    release_tree(dir.path());
+    let manifest = DirectoryManifest::build(Algorithm::Sha512, dir.path()).unwrap();
+
+    let output = Command::new(BINARY)
+        .args(["--algorithm", "sha512", "--manifest"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with(&manifest.to_string()));
+    assert!(stdout.ends_with(&format!("(54 files): {}\n", manifest.root_digest())));
+
+    let root_only = Command::new(BINARY)
+        .args(["--algorithm", "sha512", "--manifest", "--root-only"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(root_only.stdout).unwrap(),
+        format!("{}  {}\n", manifest.root_digest(), dir.path().display())
+    );
+
+    let shake = Command::new(BINARY)
+        .args(["--algorithm", "shake128", "--manifest"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(!shake.status.success());
+}
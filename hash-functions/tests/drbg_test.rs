@@ -0,0 +1,115 @@
+use hash_functions::algorithms::drbg::{Drbg, Mechanism};
+use hash_functions::algorithms::hmac_algo::hmac;
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::utils::error::Error;
+
+const MECHANISMS: [Mechanism; 2] = [Mechanism::HmacDrbg, Mechanism::HashDrbg];
+const ENTROPY: [u8; 32] = [7; 32];
+const NONCE: [u8; 16] = [9; 16];
+
+fn drbg(mechanism: Mechanism, personalization: &[u8]) -> Drbg {
+    Drbg::new(mechanism, Algorithm::Sha256, &ENTROPY, &NONCE, personalization).unwrap()
+}
+
+#[test]
+fn same_seed_same_output() {
+    for mechanism in MECHANISMS {
+        let mut first = drbg(mechanism, b"tests");
+        let mut second = drbg(mechanism, b"tests");
+        let output = first.generate_vec(100, b"").unwrap();
+        assert_eq!(output, second.generate_vec(100, b"").unwrap());
+        assert_eq!(first.reseed_counter(), 2);
+
+        // Each request moves the state forward
+        assert_ne!(first.generate_vec(100, b"").unwrap(), output);
+        // Another personalization string or additional input is another stream
+        assert_ne!(drbg(mechanism, b"other").generate_vec(100, b"").unwrap(), output);
+        assert_ne!(drbg(mechanism, b"tests").generate_vec(100, b"extra").unwrap(), output);
+    }
+}
+
+#[test]
+fn output_is_not_split_dependent() {
+    // A request returns whole blocks, so a short request is the prefix of a longer one
+    for mechanism in MECHANISMS {
+        let long = drbg(mechanism, b"").generate_vec(70, b"").unwrap();
+        assert_eq!(drbg(mechanism, b"").generate_vec(33, b"").unwrap(), long[..33]);
+    }
+}
+
+#[test]
+fn deterministic_nonces() {
+    // The secret key as entropy input and the message digest as nonce: the same message always gets
+    // the same signing nonce, different messages get unrelated ones
+    let key = [42u8; 32];
+    let nonce_for = |message: &[u8]| {
+        let digest = hmac(Algorithm::Sha256, b"message", message);
+        Drbg::new(Mechanism::HmacDrbg, Algorithm::Sha256, &key, &digest, b"signing nonce")
+            .unwrap()
+            .generate_vec(32, b"")
+            .unwrap()
+    };
+    assert_eq!(nonce_for(b"transfer 10"), nonce_for(b"transfer 10"));
+    assert_ne!(nonce_for(b"transfer 10"), nonce_for(b"transfer 11"));
+}
+
+#[test]
+fn reseed_and_prediction_resistance() {
+    for mechanism in MECHANISMS {
+        let mut reseeded = drbg(mechanism, b"");
+        reseeded.generate_vec(32, b"").unwrap();
+        reseeded.reseed(&[1; 32], b"").unwrap();
+        assert_eq!(reseeded.reseed_counter(), 1);
+        assert_ne!(reseeded.generate_vec(32, b"").unwrap(), drbg(mechanism, b"").generate_vec(32, b"").unwrap());
+
+        // A prediction resistant request is a reseed followed by a request without additional input
+        let mut expected = drbg(mechanism, b"");
+        expected.reseed(&[2; 32], b"extra").unwrap();
+        let mut output = [0u8; 48];
+        let mut resistant = drbg(mechanism, b"");
+        resistant.generate_with_prediction_resistance(&[2; 32], &mut output, b"extra").unwrap();
+        assert_eq!(output.to_vec(), expected.generate_vec(48, b"").unwrap());
+    }
+}
+
+#[test]
+fn seeded_from_the_os() {
+    for mechanism in MECHANISMS {
+        for prediction_resistance in [false, true] {
+            let mut first = Drbg::from_os(mechanism, Algorithm::Sha512, b"", prediction_resistance).unwrap();
+            let mut second = Drbg::from_os(mechanism, Algorithm::Sha512, b"", prediction_resistance).unwrap();
+            assert_eq!(first.prediction_resistance(), prediction_resistance);
+            assert_ne!(first.generate_vec(32, b"").unwrap(), second.generate_vec(32, b"").unwrap());
+            // Each prediction resistant request reseeds
+            let expected_counter = if prediction_resistance { 2 } else { 3 };
+            first.generate_vec(32, b"").unwrap();
+            assert_eq!(first.reseed_counter(), expected_counter);
+        }
+    }
+}
+
+#[test]
+fn invalid_parameters() {
+    for mechanism in MECHANISMS {
+        assert!(matches!(
+            Drbg::new(mechanism, Algorithm::Sha256, &[0; 31], &NONCE, b""),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            Drbg::new(mechanism, Algorithm::Sha256, &ENTROPY, &[0; 15], b""),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            Drbg::new(mechanism, Algorithm::Sha3_256, &ENTROPY, &NONCE, b""),
+            Err(Error::UnsupportedAlgorithm(_))
+        ));
+        // SHA-224 has a strength of 192 bits
+        assert!(Drbg::new(mechanism, Algorithm::Sha224, &[0; 24], &[0; 12], b"").is_ok());
+
+        let mut generator = drbg(mechanism, b"");
+        assert!(generator.reseed(&[0; 16], b"").is_err());
+        assert!(generator.generate_vec(65536, b"").is_ok());
+        assert!(matches!(generator.generate_vec(65537, b""), Err(Error::InvalidParameter(_))));
+        assert!(!format!("{:?}", generator).contains("value"));
+    }
+}
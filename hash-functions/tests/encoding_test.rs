@@ -0,0 +1,91 @@
+use hash_functions::algorithms::hasher::digest;
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::elements::digest::Digest;
+use hash_functions::elements::encoding::Encoding;
+use hash_functions::utils::error::Error;
+
+// RFC 4648 section 10 test vectors
+const RFC4648_INPUTS: [&str; 7] = ["", "f", "fo", "foo", "foob", "fooba", "foobar"];
+const RFC4648_BASE64: [&str; 7] = ["", "Zg==", "Zm8=", "Zm9v", "Zm9vYg==", "Zm9vYmE=", "Zm9vYmFy"];
+const RFC4648_BASE32: [&str; 7] = [
+    "",
+    "MY======",
+    "MZXQ====",
+    "MZXW6===",
+    "MZXW6YQ=",
+    "MZXW6YTB",
+    "MZXW6YTBOI======",
+];
+const RFC4648_HEX: [&str; 7] = ["", "66", "666f", "666f6f", "666f6f62", "666f6f6261", "666f6f626172"];
+
+#[test]
+fn rfc4648_vectors() {
+    for (i, input) in RFC4648_INPUTS.iter().enumerate() {
+        let unpadded = RFC4648_BASE64[i].trim_end_matches('=');
+        let expected = [
+            (Encoding::Hex, RFC4648_HEX[i]),
+            (Encoding::Base64, RFC4648_BASE64[i]),
+            (Encoding::Base64Unpadded, unpadded),
+            (Encoding::Base64Url, unpadded),
+            (Encoding::Base32, RFC4648_BASE32[i]),
+        ];
+        for (encoding, text) in expected {
+            assert_eq!(encoding.encode(input.as_bytes()), text, "{} {:?}", encoding, input);
+            assert_eq!(encoding.decode(text).unwrap(), input.as_bytes());
+        }
+    }
+}
+
+#[test]
+fn base58_and_base64url_alphabets() {
+    assert_eq!(Encoding::Base58.encode(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+    // Leading zero bytes are kept as leading ones
+    assert_eq!(Encoding::Base58.encode(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd]), "11233QC4");
+    assert_eq!(Encoding::Base58.decode("11233QC4").unwrap(), [0, 0, 0x28, 0x7f, 0xb4, 0xcd]);
+
+    let bytes = [0xfb, 0xff, 0xbf];
+    assert_eq!(Encoding::Base64.encode(&bytes), "+/+/");
+    assert_eq!(Encoding::Base64Url.encode(&bytes), "-_-_");
+}
+
+#[test]
+fn only_canonical_text_is_accepted() {
+    let rejected = [
+        (Encoding::Hex, "abc"),
+        (Encoding::Hex, "zz"),
+        (Encoding::Base64, "Zg"),
+        (Encoding::Base64, "Zh=="),
+        (Encoding::Base64, "-_-_"),
+        (Encoding::Base64Unpadded, "Zg=="),
+        (Encoding::Base64Url, "+/+/"),
+        (Encoding::Base64Url, "Zm8="),
+        (Encoding::Base32, "MY"),
+        (Encoding::Base32, "my======"),
+        (Encoding::Base32, "MZ======"),
+        (Encoding::Base58, "0OIl"),
+    ];
+    for (encoding, text) in rejected {
+        assert!(matches!(encoding.decode(text), Err(Error::InvalidEncoding(_))), "{} {:?}", encoding, text);
+    }
+    // Hex is the one case-insensitive encoding
+    assert_eq!(Encoding::Hex.decode("666F6f").unwrap(), b"foo");
+}
+
+#[test]
+fn lengths_are_validated() {
+    let salt = "4PUTzg5MuaK7gGlG0rWotXWKxtYV4lNfY6joq3QVxO8";
+    assert_eq!(Encoding::Base64Unpadded.decode_array::<32>(salt).unwrap().len(), 32);
+    assert!(matches!(
+        Encoding::Base64Unpadded.decode_exact(salt, 16),
+        Err(Error::InvalidDecodedLength { expected: 16, actual: 32 })
+    ));
+
+    let digest = digest(Algorithm::Sha3_256, b"abc");
+    for encoding in Encoding::ALL {
+        let text = digest.encode(encoding);
+        assert_eq!(Digest::decode(Algorithm::Sha3_256, encoding, &text).unwrap(), digest);
+        assert!(Digest::decode(Algorithm::Sha3_512, encoding, &text).is_err());
+        assert_eq!(encoding.to_string().parse::<Encoding>().unwrap(), encoding);
+    }
+    assert!("base65".parse::<Encoding>().is_err());
+}
@@ -0,0 +1,200 @@
+use hash_functions::elements::algorithm::{Algorithm, XofAlgorithm};
+use hash_functions::elements::multihash::HashFunction;
+use hash_functions::structures::bloom_filter::BloomFilter;
+use hash_functions::structures::cuckoo_filter::{CuckooFilter, BUCKET_SIZE};
+use hash_functions::utils::error::Error;
+
+const SHA256: HashFunction = HashFunction::Fixed(Algorithm::Sha256);
+const SHA3_256: HashFunction = HashFunction::Fixed(Algorithm::Sha3_256);
+const SHAKE128: HashFunction = HashFunction::Xof(XofAlgorithm::Shake128);
+
+fn items(prefix: &str, count: usize) -> Vec<Vec<u8>> {
+    (0..count).map(|i| format!("{} {}", prefix, i).into_bytes()).collect()
+}
+
+fn false_positives<F: Fn(&[u8]) -> bool>(contains: F, count: usize) -> usize {
+    items("absent", count).iter().filter(|item| contains(item)).count()
+}
+
+#[test]
+fn bloom_filter_rate() {
+    for function in [SHA256, SHA3_256] {
+        let mut filter = BloomFilter::new(function, 5000, 0.01).unwrap();
+        assert_eq!(filter.num_bits(), 47926);
+        assert_eq!(filter.num_hashes(), 7);
+
+        let inserted = items("breached password", 5000);
+        inserted.iter().for_each(|item| filter.insert(item));
+        assert!(inserted.iter().all(|item| filter.contains(item)));
+        assert_eq!(filter.len(), 5000);
+        assert!((filter.false_positive_rate() - 0.01).abs() < 0.001);
+
+        // 200 expected among 20000 absent items
+        let found = false_positives(|item| filter.contains(item), 20000);
+        assert!((100..400).contains(&found), "{} false positives", found);
+    }
+}
+
+#[test]
+fn bloom_filter_serialization() {
+    let mut filter = BloomFilter::new(SHAKE128, 100, 0.001).unwrap();
+    filter.insert(b"revoked key");
+    let bytes = filter.to_bytes();
+    let received = BloomFilter::from_bytes(&bytes).unwrap();
+    assert_eq!(received, filter);
+    assert!(received.contains(b"revoked key"));
+    assert_eq!(received.function(), SHAKE128);
+
+    assert!(matches!(BloomFilter::from_bytes(&bytes[..bytes.len() - 1]), Err(Error::InvalidDecodedLength { .. })));
+    let mut unknown = bytes.clone();
+    unknown[7] = 0xff;
+    assert!(matches!(BloomFilter::from_bytes(&unknown), Err(Error::UnknownMultihashCode(_))));
+}
+
+#[test]
+fn bloom_filter_forged_headers() {
+    let mut filter = BloomFilter::new(SHA256, 100, 0.01).unwrap();
+    filter.insert(b"revoked key");
+    let bytes = filter.to_bytes();
+
+    // Huge sizes are rejected from the header alone, nothing that large is allocated
+    for num_bits in [u64::MAX, 1 << 40, filter.num_bits() + 8] {
+        let mut forged = bytes.clone();
+        forged[24..32].copy_from_slice(&num_bits.to_be_bytes());
+        assert!(matches!(BloomFilter::from_bytes(&forged), Err(Error::InvalidDecodedLength { .. })));
+    }
+
+    // Item counts that the bits contradict
+    let mut forged = bytes.clone();
+    forged[36..44].copy_from_slice(&0u64.to_be_bytes());
+    assert!(matches!(BloomFilter::from_bytes(&forged), Err(Error::InvalidParameter(_))));
+    let mut forged = BloomFilter::new(SHA256, 100, 0.01).unwrap().to_bytes();
+    forged[36..44].copy_from_slice(&5u64.to_be_bytes());
+    assert!(matches!(BloomFilter::from_bytes(&forged), Err(Error::InvalidParameter(_))));
+
+    // A count that only overstates the items is accepted and cannot overflow
+    let mut forged = bytes.clone();
+    forged[36..44].copy_from_slice(&u64::MAX.to_be_bytes());
+    let mut received = BloomFilter::from_bytes(&forged).unwrap();
+    received.insert(b"another key");
+    assert_eq!(received.len(), u64::MAX);
+}
+
+#[test]
+fn bloom_filter_key() {
+    // Same key, same bits. Filters of different keys set different bits for the same items
+    let mut first = BloomFilter::with_parameters(SHA256, [1; 16], 1024, 4).unwrap();
+    let mut second = BloomFilter::with_parameters(SHA256, [1; 16], 1024, 4).unwrap();
+    let mut other = BloomFilter::with_parameters(SHA256, [2; 16], 1024, 4).unwrap();
+    for filter in [&mut first, &mut second, &mut other] {
+        filter.insert(b"item");
+    }
+    assert_eq!(first, second);
+    assert_ne!(first.to_bytes()[44..], other.to_bytes()[44..]);
+}
+
+#[test]
+fn bloom_filter_parameters() {
+    for (items, rate) in [(0, 0.01), (10, 0.0), (10, 1.0), (10, f64::NAN)] {
+        assert!(matches!(BloomFilter::new(SHA256, items, rate), Err(Error::InvalidParameter(_))));
+    }
+    assert!(BloomFilter::with_parameters(SHA256, [0; 16], 0, 1).is_err());
+    assert!(BloomFilter::with_parameters(SHA256, [0; 16], 8, 33).is_err());
+}
+
+#[test]
+fn cuckoo_filter_insert_and_remove() {
+    for function in [SHA256, SHA3_256] {
+        let mut filter = CuckooFilter::new(function, 4000).unwrap();
+        assert_eq!(filter.num_buckets(), 2048);
+
+        let inserted = items("revoked key", 7000);
+        for item in &inserted {
+            filter.insert(item).unwrap();
+        }
+        assert!(inserted.iter().all(|item| filter.contains(item)));
+        assert!(filter.load_factor() > 0.85);
+
+        // 1.2e-4 * 20000 = 2.4 expected
+        assert!(false_positives(|item| filter.contains(item), 20000) < 20);
+
+        for item in &inserted[..3500] {
+            assert!(filter.remove(item));
+        }
+        assert_eq!(filter.len(), 3500);
+        assert!(inserted[3500..].iter().all(|item| filter.contains(item)));
+        assert!(inserted[..3500].iter().filter(|item| filter.contains(item)).count() < 10);
+    }
+}
+
+#[test]
+fn cuckoo_filter_duplicates() {
+    let mut filter = CuckooFilter::new(SHA256, 100).unwrap();
+    filter.insert(b"item").unwrap();
+    filter.insert(b"item").unwrap();
+    assert!(filter.remove(b"item"));
+    assert!(filter.contains(b"item"));
+    assert!(filter.remove(b"item"));
+    assert!(!filter.contains(b"item"));
+    assert!(!filter.remove(b"item"));
+    assert!(filter.is_empty());
+}
+
+#[test]
+fn cuckoo_filter_full() {
+    let mut filter = CuckooFilter::with_parameters(SHA256, [3; 16], 16).unwrap();
+    let inserted = items("item", 100);
+    let stored = inserted.iter().take_while(|item| filter.insert(item).is_ok()).count();
+    assert!(stored > 16 * BUCKET_SIZE / 2 && stored <= 16 * BUCKET_SIZE);
+    assert!(matches!(filter.insert(b"one more"), Err(Error::FilterFull)));
+    assert!(inserted[..stored].iter().all(|item| filter.contains(item)));
+
+    // Removing an item makes room again, the full filter also survives serialization
+    let mut received = CuckooFilter::from_bytes(&filter.to_bytes()).unwrap();
+    assert_eq!(received, filter);
+    for item in &inserted[..stored / 2] {
+        assert!(received.remove(item));
+    }
+    received.insert(b"one more").unwrap();
+    assert!(inserted[stored / 2..stored].iter().all(|item| received.contains(item)));
+}
+
+#[test]
+fn cuckoo_filter_serialization() {
+    let mut filter = CuckooFilter::new(SHAKE128, 50).unwrap();
+    filter.insert(b"revoked key").unwrap();
+    let bytes = filter.to_bytes();
+    assert_eq!(bytes.len(), 51 + 2 * filter.capacity());
+    let received = CuckooFilter::from_bytes(&bytes).unwrap();
+    assert!(received.contains(b"revoked key"));
+    assert_eq!(received.len(), 1);
+
+    assert!(CuckooFilter::from_bytes(&bytes[..bytes.len() - 2]).is_err());
+    assert!(matches!(CuckooFilter::new(SHA256, 0), Err(Error::InvalidParameter(_))));
+    assert!(matches!(CuckooFilter::with_parameters(SHA256, [0; 16], 12), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn cuckoo_filter_forged_headers() {
+    let mut filter = CuckooFilter::new(SHA256, 50).unwrap();
+    filter.insert(b"revoked key").unwrap();
+    let bytes = filter.to_bytes();
+
+    // Huge sizes are rejected from the header alone, nothing that large is allocated
+    for num_buckets in [1 << 31, 1 << 63, u64::MAX] {
+        let mut forged = bytes.clone();
+        forged[24..32].copy_from_slice(&num_buckets.to_be_bytes());
+        assert!(matches!(CuckooFilter::from_bytes(&forged), Err(Error::InvalidDecodedLength { .. })));
+    }
+
+    // The item count must match the entries, a count of 0 would underflow on removal
+    for len in [0, 2, u64::MAX] {
+        let mut forged = bytes.clone();
+        forged[32..40].copy_from_slice(&len.to_be_bytes());
+        assert!(matches!(CuckooFilter::from_bytes(&forged), Err(Error::InvalidParameter(_))));
+    }
+    let mut forged = bytes.clone();
+    forged[40] = 1;
+    forged[49..51].copy_from_slice(&7u16.to_be_bytes());
+    assert!(matches!(CuckooFilter::from_bytes(&forged), Err(Error::InvalidParameter(_))));
+}
@@ -0,0 +1,70 @@
+use std::io::Cursor;
+
+use hash_functions::algorithms::hasher::{digest, digest_reader, Hasher};
+use hash_functions::elements::{algorithm::Algorithm, digest::Digest};
+
+const MESSAGE: &[u8; 3] = b"abc";
+// FIPS 180-4 and FIPS 202 examples for the "abc" message
+const ABC_DIGESTS: [(Algorithm, &str); 9] = [
+    (Algorithm::Sha224, "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7"),
+    (Algorithm::Sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+    (Algorithm::Sha384, "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7"),
+    (Algorithm::Sha512, "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"),
+    (Algorithm::Sha512_256, "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23"),
+    (Algorithm::Sha3_224, "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf"),
+    (Algorithm::Sha3_256, "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"),
+    (Algorithm::Sha3_384, "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25"),
+    (Algorithm::Sha3_512, "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"),
+];
+
+#[test]
+fn digest_abc_with_every_algorithm() {
+    for (algorithm, expected) in ABC_DIGESTS {
+        let hashed = digest(algorithm, MESSAGE);
+
+        assert_eq!(hashed.algorithm(), algorithm);
+        assert_eq!(
+            hashed.to_hex(),
+            expected
+        );
+    }
+}
+
+#[test]
+fn incremental_and_reader_match_one_shot() {
+    let message = vec![0x61u8; 200_000];
+    for algorithm in Algorithm::ALL {
+        let mut hasher = Hasher::new(algorithm);
+        for chunk in message.chunks(777) {
+            hasher.update(chunk);
+        }
+        let from_reader = digest_reader(algorithm, Cursor::new(&message)).unwrap();
+
+        assert_eq!(hasher.finalize(), digest(algorithm, &message));
+        assert_eq!(from_reader, digest(algorithm, &message));
+    }
+}
+
+#[test]
+fn parse_algorithm_names() {
+    assert_eq!("SHA-256".parse::<Algorithm>().unwrap(), Algorithm::Sha256);
+    assert_eq!("sha3_384".parse::<Algorithm>().unwrap(), Algorithm::Sha3_384);
+    assert_eq!("sha512/256".parse::<Algorithm>().unwrap(), Algorithm::Sha512_256);
+    assert!("md5".parse::<Algorithm>().is_err());
+
+    for algorithm in Algorithm::ALL {
+        assert_eq!(algorithm.name().parse::<Algorithm>().unwrap(), algorithm);
+    }
+}
+
+#[test]
+fn digest_rejects_wrong_length() {
+    let (algorithm, expected) = ABC_DIGESTS[1];
+
+    assert_eq!(
+        Digest::from_hex(algorithm, expected).unwrap(),
+        digest(algorithm, MESSAGE)
+    );
+    assert!(Digest::from_hex(Algorithm::Sha512, expected).is_err());
+    assert!(Digest::from_hex(algorithm, "not hex").is_err());
+}
@@ -0,0 +1,77 @@
+use hash_functions::algorithms::hkdf_algo::{hkdf, Hkdf};
+use hash_functions::elements::algorithm::Algorithm;
+
+// RFC 5869 test cases 1 to 3 (HMAC-SHA-256)
+const TC1_PRK: &str = "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5";
+const TC1_OKM: &str = "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865";
+const TC2_PRK: &str = "06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244";
+const TC2_OKM: &str = "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71cc30c58179ec3e87c14c01d5c1f3434f1d87";
+const TC3_PRK: &str = "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04";
+const TC3_OKM: &str = "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8";
+// Inputs of test case 1 with HMAC-SHA3-256
+const SHA3_256_OKM: &str = "0c5160501d65021deaf2c14f5abce04c5bd2635abceeba61c2edb6e8ed72674900557728f2c9f2c4c179";
+
+#[test]
+fn rfc_5869_basic() {
+    let salt: Vec<u8> = (0x00..=0x0c).collect();
+    let info: Vec<u8> = (0xf0..=0xf9).collect();
+    let derived = Hkdf::extract(Algorithm::Sha256, Some(&salt), &[0x0b; 22]);
+
+    assert_eq!(hex::encode(derived.prk()), TC1_PRK);
+    assert_eq!(
+        hex::encode(derived.expand_to_vec(&info, 42).unwrap()),
+        TC1_OKM
+    );
+}
+
+#[test]
+fn rfc_5869_longer_inputs() {
+    let ikm: Vec<u8> = (0x00..=0x4f).collect();
+    let salt: Vec<u8> = (0x60..=0xaf).collect();
+    let info: Vec<u8> = (0xb0..=0xff).collect();
+    let derived = Hkdf::extract(Algorithm::Sha256, Some(&salt), &ikm);
+
+    assert_eq!(hex::encode(derived.prk()), TC2_PRK);
+    assert_eq!(
+        hex::encode(derived.expand_to_vec(&info, 82).unwrap()),
+        TC2_OKM
+    );
+}
+
+#[test]
+fn rfc_5869_without_salt_and_info() {
+    let derived = Hkdf::extract(Algorithm::Sha256, None, &[0x0b; 22]);
+
+    assert_eq!(hex::encode(derived.prk()), TC3_PRK);
+    assert_eq!(
+        hex::encode(hkdf(Algorithm::Sha256, Some(&[]), &[0x0b; 22], &[], 42).unwrap()),
+        TC3_OKM
+    );
+}
+
+#[test]
+fn sha3_and_prk_input() {
+    let salt: Vec<u8> = (0x00..=0x0c).collect();
+    let info: Vec<u8> = (0xf0..=0xf9).collect();
+    let okm = hkdf(Algorithm::Sha3_256, Some(&salt), &[0x0b; 22], &info, 42).unwrap();
+    assert_eq!(hex::encode(okm), SHA3_256_OKM);
+
+    let from_prk = Hkdf::from_prk(Algorithm::Sha256, &hex::decode(TC1_PRK).unwrap()).unwrap();
+    assert_eq!(hex::encode(from_prk.expand_to_vec(&info, 42).unwrap()), TC1_OKM);
+    assert!(Hkdf::from_prk(Algorithm::Sha256, &[0u8; 16]).is_err());
+}
+
+#[test]
+fn independent_sub_keys_and_limits() {
+    let derived = Hkdf::extract(Algorithm::Sha512, Some(b"salt"), b"credentials");
+    let signing: [u8; 32] = derived.expand_array(b"signing key").unwrap();
+    let encryption: [u8; 32] = derived.expand_array(b"encryption key").unwrap();
+    assert_ne!(signing, encryption);
+
+    // Shorter outputs are a prefix of longer ones for the same info
+    let long = derived.expand_to_vec(b"signing key", 100).unwrap();
+    assert_eq!(&long[..32], &signing);
+
+    assert!(derived.expand_to_vec(b"", 255 * 64).is_ok());
+    assert!(derived.expand_to_vec(b"", 255 * 64 + 1).is_err());
+}
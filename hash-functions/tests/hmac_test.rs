@@ -0,0 +1,126 @@
+use hash_functions::algorithms::hmac_algo::{hmac, verify_hmac, Hmac};
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::utils::error::Error;
+
+// RFC 4231 test cases 1, 2, 6 and 7 as (key, data, HMAC-SHA-224, HMAC-SHA-256, HMAC-SHA-384, HMAC-SHA-512)
+const RFC_4231: [(&[u8], &[u8], [&str; 4]); 4] = [
+    (
+        &[0x0b; 20],
+        b"Hi There",
+        [
+            "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22",
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59cfaea9ea9076ede7f4af152e8b2fa9cb6",
+            "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+        ],
+    ),
+    (
+        b"Jefe",
+        b"what do ya want for nothing?",
+        [
+            "a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44",
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649",
+            "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+        ],
+    ),
+    (
+        &[0xaa; 131],
+        b"Test Using Larger Than Block-Size Key - Hash Key First",
+        [
+            "95e9a0db962095adaebe9b2d6f0dbce2d499f112f2d2b7273fa6870e",
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c60c2ef6ab4030fe8296248df163f44952",
+            "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+        ],
+    ),
+    (
+        &[0xaa; 131],
+        b"This is a test using a larger than block-size key and a larger than block-size data. \
+          The key needs to be hashed before being used by the HMAC algorithm.",
+        [
+            "3a854166ac5d9f023f54d517d0b39dbd946770db9c2b95c9f6f565d1",
+            "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+            "6617178e941f020d351e2f254e8fd32c602420feb0b8fb9adccebb82461e99c5a678cc31e799176d3860e6110c46523e",
+            "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58",
+        ],
+    ),
+];
+const SHA2_ALGORITHMS: [Algorithm; 4] = [Algorithm::Sha224, Algorithm::Sha256, Algorithm::Sha384, Algorithm::Sha512];
+
+// NIST HMAC-SHA3 examples: key 00 01 02 .. of the output length and
+// the message "Sample message for keylen<blocklen"
+const NIST_SHA3_MESSAGE: &[u8; 34] = b"Sample message for keylen<blocklen";
+const NIST_SHA3: [(Algorithm, &str); 4] = [
+    (Algorithm::Sha3_224, "332cfd59347fdb8e576e77260be4aba2d6dc53117b3bfb52c6d18c04"),
+    (Algorithm::Sha3_256, "4fe8e202c4f058e8dddc23d8c34e467343e23555e24fc2f025d598f558f67205"),
+    (Algorithm::Sha3_384, "d588a3c51f3f2d906e8298c1199aa8ff6296218127f6b38a90b6afe2c5617725bc99987f79b22a557b6520db710b7f42"),
+    (Algorithm::Sha3_512, "4efd629d6c71bf86162658f29943b1c308ce27cdfa6db0d9c3ce81763f9cbce5f7ebe9868031db1a8f8eb7b6b95e5c5e3f657a8996c86a2f6527e307f0213196"),
+];
+
+#[test]
+fn rfc_4231_vectors() {
+    for (key, data, tags) in RFC_4231 {
+        for (algorithm, tag) in SHA2_ALGORITHMS.iter().zip(tags) {
+            let tag = hex::decode(tag).unwrap();
+
+            assert_eq!(hmac(*algorithm, key, data), tag);
+            assert!(verify_hmac(*algorithm, key, data, &tag).is_ok());
+        }
+    }
+}
+
+#[test]
+fn rfc_4231_truncated_tag() {
+    let key = [0x0c; 20];
+    let mut mac = Hmac::new(Algorithm::Sha256, &key);
+    mac.update(b"Test With Truncation");
+
+    assert!(mac.clone().verify_truncated(&hex::decode("a3b6167473100ee06e0c796c2955552b").unwrap()).is_ok());
+    assert!(mac.clone().verify_truncated(&hex::decode("a3b6167473100ee06e0c796c2955552c").unwrap()).is_err());
+    // Shorter than half of the output
+    assert!(mac.clone().verify_truncated(&hex::decode("a3b6167473100ee0").unwrap()).is_err());
+    // Shorter than 80 bits
+    assert!(matches!(mac.verify_truncated(&hex::decode("a3b6167473100ee06e").unwrap()), Err(Error::InvalidMac)));
+}
+
+#[test]
+fn nist_sha3_vectors() {
+    for (algorithm, tag) in NIST_SHA3 {
+        let key: Vec<u8> = (0..algorithm.output_len() as u8).collect();
+
+        assert_eq!(
+            hex::encode(hmac(algorithm, &key, NIST_SHA3_MESSAGE)),
+            tag
+        );
+    }
+}
+
+#[test]
+fn incremental_matches_one_shot() {
+    let key = b"webhook secret";
+    let payload = br#"{"event":"push","ref":"refs/heads/main"}"#;
+    for algorithm in Algorithm::ALL {
+        let mut mac = Hmac::new(algorithm, key);
+        for chunk in payload.chunks(5) {
+            mac.update(chunk);
+        }
+        let tag = mac.finalize();
+
+        assert_eq!(tag.len(), algorithm.output_len());
+        assert_eq!(tag, hmac(algorithm, key, payload));
+    }
+}
+
+#[test]
+fn reject_forged_tags() {
+    let key = b"webhook secret";
+    let payload = b"amount=100";
+    let mut tag = hmac(Algorithm::Sha256, key, payload);
+
+    assert!(verify_hmac(Algorithm::Sha256, b"other secret", payload, &tag).is_err());
+    assert!(verify_hmac(Algorithm::Sha256, key, b"amount=999", &tag).is_err());
+    assert!(verify_hmac(Algorithm::Sha256, key, payload, &tag[..16]).is_err());
+    tag[31] ^= 1;
+    assert!(verify_hmac(Algorithm::Sha256, key, payload, &tag).is_err());
+}
@@ -0,0 +1,70 @@
+use std::process::Command;
+
+use hash_functions::algorithms::hasher::digest;
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::elements::multihash::Multihash;
+
+const BINARY: &str = env!("CARGO_BIN_EXE_hash-functions");
+const MESSAGE: &[u8] = b"abc";
+
+// Keccak and SHA-3 share the permutation and the rate, only the padding differs,
+// yet the digests of the same input have nothing in common
+const KECCAK_AND_SHA3: [(Algorithm, Algorithm, &str); 4] = [
+    (Algorithm::Keccak224, Algorithm::Sha3_224, "c30411768506ebe1c2871b1ee2e87d38df342317300a9b97a95ec6a8"),
+    (Algorithm::Keccak256, Algorithm::Sha3_256, "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"),
+    (Algorithm::Keccak384, Algorithm::Sha3_384, "f7df1165f033337be098e7d288ad6a2f74409d7a60b49c36642218de161b1f99f8c681e4afaf31a34db29fb763e3c28e"),
+    (Algorithm::Keccak512, Algorithm::Sha3_512, "18587dc2ea106b9a1563e32b3312421ca164c7f1f07bc922a9c83d77cea3a1e5d0c69910739025372dc14ac9642629379540c17e2a65b19d77aa511a9d00bb96"),
+];
+
+// Ethereum values: the hash of empty data and the selector of the ERC-20 `transfer` function
+const KECCAK256_EMPTY: &str = "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+const SHA3_256_EMPTY: &str = "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a";
+const TRANSFER_SELECTOR: &str = "a9059cbb";
+
+#[test]
+fn keccak_differs_from_sha3() {
+    for (keccak, sha3, expected) in KECCAK_AND_SHA3 {
+        let keccak_digest = digest(keccak, MESSAGE);
+        let sha3_digest = digest(sha3, MESSAGE);
+
+        assert_eq!(keccak_digest.to_hex(), expected);
+        assert_eq!(keccak.output_len(), sha3.output_len());
+        assert_ne!(keccak_digest.as_bytes(), sha3_digest.as_bytes());
+        // Digests keep their algorithm, so they are never equal even with the same bytes
+        assert_ne!(keccak_digest, sha3_digest);
+    }
+
+    assert_eq!(digest(Algorithm::Keccak256, b"").to_hex(), KECCAK256_EMPTY);
+    assert_eq!(digest(Algorithm::Sha3_256, b"").to_hex(), SHA3_256_EMPTY);
+}
+
+#[test]
+fn ethereum_function_selector() {
+    let hash = digest(Algorithm::Keccak256, b"transfer(address,uint256)");
+    assert_eq!(hex::encode(&hash.as_bytes()[..4]), TRANSFER_SELECTOR);
+}
+
+#[test]
+fn names_and_multihash_codes() {
+    assert_eq!("keccak256".parse::<Algorithm>().unwrap(), Algorithm::Keccak256);
+    assert_eq!("Keccak-512".parse::<Algorithm>().unwrap(), Algorithm::Keccak512);
+    assert_eq!(Algorithm::Keccak384.to_string(), "Keccak-384");
+
+    let multihash = Multihash::hash(Algorithm::Keccak256, b"");
+    assert_eq!(multihash.to_hex(), format!("1b20{}", KECCAK256_EMPTY));
+    assert_eq!(Multihash::hash(Algorithm::Keccak224, b"").to_hex()[..4], *"1a1c");
+}
+
+#[test]
+fn keccak_from_binary() {
+    let output = Command::new(BINARY)
+        .args(["--algorithm", "keccak-256", "--tag", "/dev/null"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("KECCAK-256 (/dev/null) = {}\n", KECCAK256_EMPTY)
+    );
+}
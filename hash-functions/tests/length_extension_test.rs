@@ -0,0 +1,159 @@
+use std::process::Command;
+
+use hash_functions::algorithms::hmac_algo::{hmac, verify_hmac};
+use hash_functions::attacks::length_extension::{forge, padding, secret_prefix_mac, verify_secret_prefix_mac};
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::elements::digest::Digest;
+use hash_functions::utils::error::Error;
+
+const BINARY: &str = env!("CARGO_BIN_EXE_hash-functions");
+const SECRET: &[u8] = b"server-side secret key";
+const MESSAGE: &[u8] = b"user=bob&role=user";
+const EXTENSION: &[u8] = b"&role=admin";
+
+#[test]
+fn padding_completes_the_last_block() {
+    for length in 0..300u64 {
+        for (algorithm, block) in [(Algorithm::Sha256, 64), (Algorithm::Sha512, 128), (Algorithm::Sha3_256, 136)] {
+            let padding = padding(algorithm, length).unwrap();
+            assert_eq!((length as usize + padding.len()) % block, 0);
+            assert!(!padding.is_empty() && padding.len() <= block + 16);
+        }
+    }
+    assert_eq!(padding(Algorithm::Sha256, 3).unwrap(), [&[0x80][..], &[0; 52], &24u64.to_be_bytes()].concat());
+
+    // The length in bits must fit the u64 of the padding
+    assert!(padding(Algorithm::Sha512, u64::MAX / 8).is_ok());
+    assert!(matches!(padding(Algorithm::Sha256, u64::MAX / 8 + 1), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn forgery_against_sha2() {
+    for algorithm in [Algorithm::Sha256, Algorithm::Sha512] {
+        for secret_len in [0, 1, 22, 55, 64, 111, 200] {
+            let secret = vec![0x5a; secret_len];
+            for extension in [&b""[..], EXTENSION, &[0x41; 150]] {
+                let tag = secret_prefix_mac(algorithm, &secret, MESSAGE);
+                let forgery = forge(&tag, secret_len, MESSAGE, extension).unwrap();
+
+                assert!(forgery.message.starts_with(MESSAGE) && forgery.message.ends_with(extension));
+                assert!(verify_secret_prefix_mac(algorithm, &secret, &forgery.message, &forgery.tag).is_ok());
+            }
+        }
+    }
+}
+
+#[test]
+fn secret_length_is_found_by_trying() {
+    let tag = secret_prefix_mac(Algorithm::Sha256, SECRET, MESSAGE);
+    let accepted: Vec<usize> = (0..64)
+        .filter(|guess| {
+            let forgery = forge(&tag, *guess, MESSAGE, EXTENSION).unwrap();
+            verify_secret_prefix_mac(Algorithm::Sha256, SECRET, &forgery.message, &forgery.tag).is_ok()
+        })
+        .collect();
+
+    assert_eq!(accepted, [SECRET.len()]);
+}
+
+#[test]
+fn secret_lengths_beyond_the_padding() {
+    let tag = secret_prefix_mac(Algorithm::Sha256, SECRET, MESSAGE);
+    for secret_len in [3_000_000_000_000_000_000, usize::MAX] {
+        assert!(matches!(forge(&tag, secret_len, MESSAGE, EXTENSION), Err(Error::InvalidParameter(_))));
+    }
+}
+
+#[test]
+fn sponges_and_truncated_hashes_resist() {
+    for algorithm in [Algorithm::Sha3_256, Algorithm::Sha3_512, Algorithm::Keccak256] {
+        let tag = secret_prefix_mac(algorithm, SECRET, MESSAGE);
+        let forgery = forge(&tag, SECRET.len(), MESSAGE, EXTENSION).unwrap();
+
+        assert!(verify_secret_prefix_mac(algorithm, SECRET, &forgery.message, &forgery.tag).is_err());
+    }
+
+    for algorithm in [Algorithm::Sha224, Algorithm::Sha384, Algorithm::Sha512_256] {
+        let tag = secret_prefix_mac(algorithm, SECRET, MESSAGE);
+        assert!(matches!(
+            forge(&tag, SECRET.len(), MESSAGE, EXTENSION),
+            Err(Error::UnsupportedAlgorithm(_))
+        ));
+    }
+}
+
+#[test]
+fn hmac_resists() {
+    let tag = Digest::new(Algorithm::Sha256, hmac(Algorithm::Sha256, SECRET, MESSAGE)).unwrap();
+    for guess in 0..128 {
+        let forgery = forge(&tag, guess, MESSAGE, EXTENSION).unwrap();
+        assert!(verify_hmac(Algorithm::Sha256, SECRET, &forgery.message, forgery.tag.as_bytes()).is_err());
+    }
+}
+
+#[test]
+fn forgery_from_binary() {
+    let tag = secret_prefix_mac(Algorithm::Sha256, SECRET, MESSAGE);
+    let secret_len = SECRET.len().to_string();
+    let output = Command::new(BINARY)
+        .args(["length-extension", "--message", "user=bob&role=user", "--extension", "&role=admin"])
+        .args(["--tag", &tag.to_hex(), "--secret-len", &secret_len])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+    let message = hex::decode(lines.next().unwrap().strip_prefix("message: ").unwrap()).unwrap();
+    let forged_tag = Digest::from_hex(Algorithm::Sha256, lines.next().unwrap().strip_prefix("tag: ").unwrap()).unwrap();
+    assert!(verify_secret_prefix_mac(Algorithm::Sha256, SECRET, &message, &forged_tag).is_ok());
+
+    // Unknown length: one candidate per length, the one of the real length verifies
+    let output = Command::new(BINARY)
+        .args(["length-extension", "--message", "user=bob&role=user", "--extension", "&role=admin"])
+        .args(["--tag", &tag.to_hex(), "--max-secret-len", "40"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 41 * 3);
+    let accepted: Vec<&str> = lines
+        .chunks(3)
+        .filter(|candidate| {
+            let message = hex::decode(candidate[1].strip_prefix("message: ").unwrap()).unwrap();
+            let tag = Digest::from_hex(Algorithm::Sha256, candidate[2].strip_prefix("tag: ").unwrap()).unwrap();
+            verify_secret_prefix_mac(Algorithm::Sha256, SECRET, &message, &tag).is_ok()
+        })
+        .map(|candidate| candidate[0])
+        .collect();
+    assert_eq!(accepted, [format!("secret length: {}", SECRET.len())]);
+
+    // Lengths the padding cannot count and too many guesses are refused instead of crashing
+    for length_args in [["--secret-len", "3000000000000000000"], ["--max-secret-len", "18446744073709551615"]] {
+        let output = Command::new(BINARY)
+            .args(["length-extension", "--message", "a", "--extension", "b", "--tag", &"0".repeat(64)])
+            .args(length_args)
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+        assert!(!String::from_utf8(output.stderr).unwrap().contains("panicked"));
+    }
+
+    // The tag alone is not enough
+    let output = Command::new(BINARY)
+        .args(["length-extension", "--message", "m", "--extension", "e", "--tag", &tag.to_hex()])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let demonstration = Command::new(BINARY)
+        .args(["length-extension", "--message", "m", "--extension", "e", "--secret", "key"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(demonstration.stdout).unwrap();
+    assert!(stdout.contains("SHA-256(secret || message): forged tag ACCEPTED"));
+    assert!(stdout.contains("SHA3-256(secret || message): forged tag rejected"));
+    assert!(stdout.contains("HMAC-SHA-256: forged tag rejected"));
+}
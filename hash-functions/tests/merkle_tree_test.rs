@@ -0,0 +1,121 @@
+use hash_functions::algorithms::hasher::digest;
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::structures::merkle_tree::{ConsistencyProof, InclusionProof, MerkleTree};
+
+// Roots of the Certificate Transparency reference tree, for every size from 1 to 8
+const CT_ROOTS: [&str; 8] = [
+    "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+    "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+    "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+    "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+    "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+    "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+    "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+    "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+];
+
+fn ct_leaves() -> Vec<Vec<u8>> {
+    vec![
+        vec![],
+        vec![0x00],
+        vec![0x10],
+        vec![0x20, 0x21],
+        vec![0x30, 0x31],
+        vec![0x40, 0x41, 0x42, 0x43],
+        (0x50..=0x57).collect(),
+        (0x60..=0x6f).collect(),
+    ]
+}
+
+fn release_set(size: usize) -> Vec<Vec<u8>> {
+    (0..size).map(|i| format!("artifact-{}.tar.gz", i).into_bytes()).collect()
+}
+
+#[test]
+fn certificate_transparency_roots() {
+    let tree = MerkleTree::from_leaves(Algorithm::Sha256, &ct_leaves());
+
+    for (size, root) in CT_ROOTS.iter().enumerate() {
+        assert_eq!(tree.root_at(size + 1).unwrap().to_hex(), *root);
+    }
+    assert_eq!(
+        MerkleTree::new(Algorithm::Sha256).root(),
+        digest(Algorithm::Sha256, b"")
+    );
+}
+
+#[test]
+fn inclusion_proofs_for_every_leaf() {
+    for algorithm in [Algorithm::Sha256, Algorithm::Sha3_512] {
+        let leaves = release_set(21);
+        let tree = MerkleTree::from_leaves(algorithm, &leaves);
+        for size in 1..=leaves.len() {
+            let root = tree.root_at(size).unwrap();
+            for (index, leaf) in leaves.iter().enumerate().take(size) {
+                let proof = tree.inclusion_proof_at(index, size).unwrap();
+
+                assert!(proof.verify(leaf, &root).is_ok());
+                assert!(proof.verify(b"artifact-x.tar.gz", &root).is_err());
+            }
+        }
+    }
+}
+
+#[test]
+fn tampered_inclusion_proofs_fail() {
+    let leaves = release_set(13);
+    let tree = MerkleTree::from_leaves(Algorithm::Sha256, &leaves);
+    let root = tree.root();
+    let proof = tree.inclusion_proof(6).unwrap();
+
+    let wrong_index = InclusionProof { leaf_index: 7, ..proof.clone() };
+    assert!(wrong_index.verify(&leaves[6], &root).is_err());
+
+    let mut short_path = proof.clone();
+    short_path.path.pop();
+    assert!(short_path.verify(&leaves[6], &root).is_err());
+
+    let other_algorithm = MerkleTree::from_leaves(Algorithm::Sha3_256, &leaves).root();
+    assert!(proof.verify(&leaves[6], &other_algorithm).is_err());
+    assert!(tree.inclusion_proof(13).is_err());
+}
+
+#[test]
+fn consistency_proofs_between_sizes() {
+    let leaves = release_set(20);
+    let tree = MerkleTree::from_leaves(Algorithm::Sha256, &leaves);
+    for new_size in 0..=leaves.len() {
+        let new_root = tree.root_at(new_size).unwrap();
+        for old_size in 0..=new_size {
+            let old_root = tree.root_at(old_size).unwrap();
+            let proof = tree.consistency_proof_between(old_size, new_size).unwrap();
+
+            assert!(proof.verify(&old_root, &new_root).is_ok());
+            if old_size > 0 && old_size < new_size {
+                // A rewritten or truncated history does not verify against the published root
+                let truncated = tree.root_at(old_size - 1).unwrap();
+                let mut rewritten = MerkleTree::from_leaves(Algorithm::Sha256, &release_set(old_size - 1));
+                rewritten.push(b"malicious.tar.gz");
+
+                assert!(proof.verify(&rewritten.root(), &new_root).is_err());
+                assert!(proof.verify(&truncated, &new_root).is_err());
+            }
+        }
+    }
+}
+
+#[test]
+fn tampered_consistency_proofs_fail() {
+    let tree = MerkleTree::from_leaves(Algorithm::Sha256, &release_set(11));
+    let old_root = tree.root_at(6).unwrap();
+    let new_root = tree.root();
+    let proof = tree.consistency_proof(6).unwrap();
+
+    let mut swapped = proof.clone();
+    swapped.path.swap(0, 1);
+    assert!(swapped.verify(&old_root, &new_root).is_err());
+
+    let wrong_size = ConsistencyProof { old_size: 5, ..proof.clone() };
+    assert!(wrong_size.verify(&old_root, &new_root).is_err());
+    assert!(tree.consistency_proof(12).is_err());
+}
@@ -0,0 +1,117 @@
+use hash_functions::signatures::mss::{MssKeyFile, MssPublicKey, MssSignature, MssSigningKey};
+use hash_functions::signatures::primitives::{HashFamily, N};
+use hash_functions::signatures::wots::LEN;
+use hash_functions::utils::error::Error;
+use tempfile::TempDir;
+
+// Root of `MssSigningKey::from_seed(Sha256, 2, [0; 32])`, from a Python implementation of the
+// L-tree and tree hash algorithms of RFC 8391
+const ROOT_HEIGHT_2: &str = "616e9d4ddb176751472b6e43a3308db09ceebc6077b68eed94ffc5ed3b3ce727";
+
+#[test]
+fn root_matches_reference() {
+    let key = MssSigningKey::from_seed(HashFamily::Sha256, 2, [0; N]).unwrap();
+    assert_eq!(hex::encode(key.public_key().root()), ROOT_HEIGHT_2);
+}
+
+#[test]
+fn every_leaf_signs_once() {
+    for (family, height) in [(HashFamily::Sha256, 3), (HashFamily::Shake256, 1)] {
+        let mut key = MssSigningKey::generate(family, height).unwrap();
+        let public_key = key.public_key();
+
+        for index in 0..1u32 << height {
+            let message = format!("message {}", index);
+            let signature = key.sign(message.as_bytes()).unwrap();
+            assert_eq!(signature.index(), index);
+            assert_eq!(signature.auth_path().len(), height as usize);
+            assert!(public_key.verify(message.as_bytes(), &signature).is_ok());
+            assert!(public_key.verify(b"another message", &signature).is_err());
+        }
+
+        assert_eq!(key.remaining(), 0);
+        assert!(matches!(key.sign(b"one more"), Err(Error::SigningKeyExhausted)));
+    }
+}
+
+#[test]
+fn forged_signatures_are_rejected() {
+    let mut key = MssSigningKey::from_seed(HashFamily::Sha256, 4, [1; N]).unwrap();
+    let public_key = key.public_key();
+    key.sign(b"first").unwrap();
+    let signature = key.sign(b"second").unwrap();
+    let bytes = signature.to_bytes();
+    assert_eq!(bytes.len(), 4 + N + LEN * N + 4 * N);
+
+    // Another index, a damaged authentication path, a damaged one-time signature
+    for position in [3, bytes.len() - 1, 4 + N + 100] {
+        let mut tampered = bytes.clone();
+        tampered[position] ^= 1;
+        let tampered = MssSignature::from_bytes(4, &tampered).unwrap();
+        assert!(matches!(public_key.verify(b"second", &tampered), Err(Error::InvalidSignature)));
+    }
+
+    // Index beyond the tree
+    let mut beyond = bytes.clone();
+    beyond[..4].copy_from_slice(&16u32.to_be_bytes());
+    assert!(public_key.verify(b"second", &MssSignature::from_bytes(4, &beyond).unwrap()).is_err());
+
+    // Another key
+    let other = MssSigningKey::from_seed(HashFamily::Sha256, 4, [2; N]).unwrap().public_key();
+    assert!(other.verify(b"second", &signature).is_err());
+    assert!(MssSignature::from_bytes(5, &bytes).is_err());
+}
+
+#[test]
+fn serialization() {
+    let mut key = MssSigningKey::from_seed(HashFamily::Sha256, 3, [3; N]).unwrap();
+    key.sign(b"first").unwrap();
+
+    let reloaded = MssSigningKey::from_bytes(&key.to_bytes()).unwrap();
+    assert_eq!(reloaded.next_index(), 1);
+    assert_eq!(reloaded.public_key(), key.public_key());
+    assert!(!format!("{:?}", reloaded).contains("secret"));
+
+    let public_key = MssPublicKey::from_bytes(&key.public_key().to_bytes()).unwrap();
+    let signature = key.sign(b"second").unwrap();
+    let signature = MssSignature::from_bytes(public_key.height(), &signature.to_bytes()).unwrap();
+    assert!(public_key.verify(b"second", &signature).is_ok());
+
+    // A damaged key is detected by its root
+    let mut damaged = key.to_bytes();
+    damaged[10] ^= 1;
+    assert!(matches!(MssSigningKey::from_bytes(&damaged), Err(Error::DigestMismatch)));
+    assert!(matches!(
+        MssSigningKey::from_seed(HashFamily::Sha256, 0, [0; N]),
+        Err(Error::InvalidParameter(_))
+    ));
+}
+
+#[test]
+fn key_file_persists_the_index() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("signing.key");
+    let key = MssSigningKey::from_seed(HashFamily::Sha256, 3, [4; N]).unwrap();
+    let public_key = key.public_key();
+
+    let mut file = MssKeyFile::create(&path, key).unwrap();
+    let first = file.sign(b"first").unwrap();
+    drop(file);
+
+    // A process restarting from the file continues after the used leaf
+    let mut file = MssKeyFile::open(&path).unwrap();
+    assert_eq!(file.key().next_index(), 1);
+    let second = file.sign(b"second").unwrap();
+    assert_eq!((first.index(), second.index()), (0, 1));
+    assert!(public_key.verify(b"second", &second).is_ok());
+
+    // Only one process signs with the key at a time
+    assert!(matches!(MssKeyFile::open(&path), Err(Error::KeyFileInUse(_))));
+    drop(file);
+    assert_eq!(MssKeyFile::open(&path).unwrap().key().next_index(), 2);
+    assert!(!dir.path().join("signing.key.lock").exists());
+
+    // Creating a key over an existing one would reset its index
+    let again = MssSigningKey::from_seed(HashFamily::Sha256, 3, [4; N]).unwrap();
+    assert!(matches!(MssKeyFile::create(&path, again), Err(Error::Io(_))));
+}
@@ -0,0 +1,88 @@
+use hash_functions::algorithms::hasher::digest;
+use hash_functions::elements::algorithm::{Algorithm, XofAlgorithm};
+use hash_functions::elements::multihash::{HashFunction, Multihash};
+use hash_functions::utils::error::Error;
+
+// Example of the multihash specification: sha2-256 of "Merkle–Damgård"
+const MERKLE_DAMGARD: &str = "Merkle–Damgård";
+const MERKLE_DAMGARD_SHA256: &str = "122041dd7b6443542e75701aa98a0c235951a28a0d851b11564d20022ab11d2589a8";
+
+#[test]
+fn specification_example() {
+    let multihash = Multihash::hash(Algorithm::Sha256, MERKLE_DAMGARD.as_bytes());
+
+    assert_eq!(multihash.to_hex(), MERKLE_DAMGARD_SHA256);
+    assert_eq!(Multihash::from_hex(MERKLE_DAMGARD_SHA256).unwrap(), multihash);
+    assert!(multihash.verify(MERKLE_DAMGARD.as_bytes()).is_ok());
+}
+
+#[test]
+fn every_function_round_trips() {
+    let expected_prefixes = [
+        (Algorithm::Sha224, "93201c"),
+        (Algorithm::Sha256, "1220"),
+        (Algorithm::Sha384, "2030"),
+        (Algorithm::Sha512, "1340"),
+        (Algorithm::Sha512_256, "952020"),
+        (Algorithm::Sha3_224, "171c"),
+        (Algorithm::Sha3_256, "1620"),
+        (Algorithm::Sha3_384, "1530"),
+        (Algorithm::Sha3_512, "1440"),
+    ];
+    for (algorithm, prefix) in expected_prefixes {
+        let digest = digest(algorithm, b"abc");
+        let multihash = Multihash::from(&digest);
+
+        assert!(multihash.to_hex().starts_with(prefix), "{}", algorithm);
+        let parsed = Multihash::from_bytes(&multihash.to_bytes()).unwrap();
+        assert_eq!(parsed.function(), HashFunction::Fixed(algorithm));
+        assert_eq!(parsed.to_digest().unwrap(), digest);
+        assert!(parsed.verify(b"abc").is_ok());
+        assert!(matches!(parsed.verify(b"abd"), Err(Error::DigestMismatch)));
+    }
+
+    for (algorithm, code) in [(XofAlgorithm::Shake128, 0x18), (XofAlgorithm::Shake256, 0x19)] {
+        let function = HashFunction::Xof(algorithm);
+        assert_eq!(function.code(), code);
+        assert_eq!(HashFunction::from_code(code).unwrap(), function);
+    }
+}
+
+#[test]
+fn shake_and_truncated_digests() {
+    let shake = Multihash::new(
+        HashFunction::Xof(XofAlgorithm::Shake128),
+        hex::decode("7f9c2ba4e88f827d616045507605853e").unwrap(),
+    )
+    .unwrap();
+    assert_eq!(shake.to_hex(), "18107f9c2ba4e88f827d616045507605853e");
+    assert!(shake.verify(b"").is_ok());
+    assert!(shake.to_digest().is_err());
+
+    let sha256 = digest(Algorithm::Sha256, b"abc");
+    let truncated = Multihash::new(HashFunction::Fixed(Algorithm::Sha256), sha256.as_bytes()[..20].to_vec()).unwrap();
+    assert!(truncated.to_hex().starts_with("1214"));
+    assert!(truncated.verify(b"abc").is_ok());
+    assert!(truncated.to_digest().is_err());
+}
+
+#[test]
+fn malformed_multihashes_are_rejected() {
+    let valid = hex::decode(MERKLE_DAMGARD_SHA256).unwrap();
+
+    // Unknown code, sha2-512/224 is not supported
+    assert!(matches!(Multihash::from_hex("942002aabb"), Err(Error::UnknownMultihashCode(0x1014))));
+    // Non minimal varint for the code
+    assert!(Multihash::from_bytes(&[&[0x92, 0x00], &valid[1..]].concat()).is_err());
+    // Truncated varint and digest
+    assert!(Multihash::from_bytes(&[0x95]).is_err());
+    assert!(Multihash::from_bytes(&valid[..valid.len() - 1]).is_err());
+    // Trailing byte, unless the caller reads a prefix
+    let extended = [valid.as_slice(), &[0x00]].concat();
+    assert!(Multihash::from_bytes(&extended).is_err());
+    assert_eq!(Multihash::read(&extended).unwrap().1, valid.len());
+    // Longer than the output of the function, and empty
+    assert!(Multihash::from_hex(&format!("1221{}00", &MERKLE_DAMGARD_SHA256[4..])).is_err());
+    assert!(Multihash::from_hex("1200").is_err());
+    assert!(Multihash::from_hex("12").is_err());
+}
@@ -0,0 +1,118 @@
+use hash_functions::signatures::lamport::{LamportPublicKey, LamportSignature, LamportSigningKey};
+use hash_functions::signatures::primitives::{HashFamily, N};
+use hash_functions::signatures::wots::{WotsPublicKey, WotsSignature, WotsSigningKey, LEN};
+use hash_functions::utils::error::Error;
+
+const MESSAGE: &[u8] = b"abc";
+
+// SHA-256 of the serialized values, from a Python implementation written from RFC 8391:
+// the public key and the signature of "abc" of `WotsSigningKey::from_seed(Sha256, [0; 32])`
+// and the public key of `LamportSigningKey::from_seed(Sha256, [0, 1, .., 31])`
+const WOTS_PUBLIC_KEY: &str = "d191f5cf337682c52a08b5e4877ff33ca5d39be06503a1d1cdd7b2a61c347a3e";
+const WOTS_SIGNATURE: &str = "58467e3b1df9f518505a6c556c398e9583d2a7d0a45a21a98a5f6c062391ee2d";
+const LAMPORT_PUBLIC_KEY: &str = "37bb722753bd3478f1aa2fbf4f5928cae3122ac97dfb5e1153e71696606724e9";
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(HashFamily::Sha256.hash(bytes))
+}
+
+#[test]
+fn lamport_sign_and_verify() {
+    for family in HashFamily::ALL {
+        let mut key = LamportSigningKey::generate(family).unwrap();
+        let public_key = key.public_key();
+        let signature = key.sign(MESSAGE).unwrap();
+
+        assert!(public_key.verify(MESSAGE, &signature).is_ok());
+        assert!(matches!(public_key.verify(b"abd", &signature), Err(Error::InvalidSignature)));
+        assert_eq!(signature.to_bytes().len(), 256 * N);
+        assert_eq!(public_key.to_bytes().len(), 1 + 512 * N);
+    }
+
+    let key = LamportSigningKey::from_seed(HashFamily::Sha256, std::array::from_fn(|i| i as u8));
+    assert_eq!(sha256_hex(&key.public_key().to_bytes()[1..]), LAMPORT_PUBLIC_KEY);
+}
+
+#[test]
+fn wots_sign_and_verify() {
+    for family in HashFamily::ALL {
+        let mut key = WotsSigningKey::generate(family).unwrap();
+        let public_key = key.public_key();
+        let signature = key.sign(MESSAGE).unwrap();
+
+        assert!(public_key.verify(MESSAGE, &signature).is_ok());
+        assert!(matches!(public_key.verify(b"abd", &signature), Err(Error::InvalidSignature)));
+        assert_eq!(signature.to_bytes().len(), LEN * N);
+    }
+
+    let mut key = WotsSigningKey::from_seed(HashFamily::Sha256, [0; N]);
+    let chains: Vec<u8> = key.public_key().chains().concat();
+    assert_eq!(sha256_hex(&chains), WOTS_PUBLIC_KEY);
+    assert_eq!(sha256_hex(&key.sign(MESSAGE).unwrap().to_bytes()), WOTS_SIGNATURE);
+}
+
+#[test]
+fn keys_sign_once() {
+    let mut lamport = LamportSigningKey::from_seed(HashFamily::Sha256, [7; N]);
+    assert!(!lamport.is_used());
+    lamport.sign(b"first").unwrap();
+    assert!(lamport.is_used());
+    assert!(matches!(lamport.sign(b"second"), Err(Error::OneTimeKeyReused)));
+    assert!(matches!(lamport.sign(b"first"), Err(Error::OneTimeKeyReused)));
+
+    let mut wots = WotsSigningKey::from_seed(HashFamily::Shake256, [7; N]);
+    wots.sign(b"first").unwrap();
+    assert!(matches!(wots.sign(b"second"), Err(Error::OneTimeKeyReused)));
+
+    // The flag survives a save and a reload
+    let mut reloaded = WotsSigningKey::from_bytes(&wots.to_bytes()).unwrap();
+    assert!(reloaded.is_used());
+    assert!(matches!(reloaded.sign(b"second"), Err(Error::OneTimeKeyReused)));
+    let mut reloaded = LamportSigningKey::from_bytes(&lamport.to_bytes()).unwrap();
+    assert!(matches!(reloaded.sign(b"second"), Err(Error::OneTimeKeyReused)));
+}
+
+#[test]
+fn same_seed_same_key() {
+    let first = WotsSigningKey::from_seed(HashFamily::Sha256, [1; N]).public_key();
+    let second = WotsSigningKey::from_seed(HashFamily::Sha256, [1; N]).public_key();
+    let other_family = WotsSigningKey::from_seed(HashFamily::Shake256, [1; N]).public_key();
+    assert_eq!(first, second);
+    assert_ne!(first.chains(), other_family.chains());
+
+    let lamport = LamportSigningKey::from_seed(HashFamily::Sha256, [1; N]).public_key();
+    assert_eq!(lamport, LamportSigningKey::from_seed(HashFamily::Sha256, [1; N]).public_key());
+}
+
+#[test]
+fn serialization() {
+    let mut lamport = LamportSigningKey::from_seed(HashFamily::Shake256, [2; N]);
+    let public_key = LamportPublicKey::from_bytes(&lamport.public_key().to_bytes()).unwrap();
+    let signature = LamportSignature::from_bytes(&lamport.sign(MESSAGE).unwrap().to_bytes()).unwrap();
+    assert_eq!(public_key.family(), HashFamily::Shake256);
+    assert!(public_key.verify(MESSAGE, &signature).is_ok());
+
+    let mut wots = WotsSigningKey::from_seed(HashFamily::Shake256, [2; N]);
+    let public_key = WotsPublicKey::from_bytes(&wots.public_key().to_bytes()).unwrap();
+    let signature = WotsSignature::from_bytes(&wots.sign(MESSAGE).unwrap().to_bytes()).unwrap();
+    assert!(public_key.verify(MESSAGE, &signature).is_ok());
+
+    assert!(matches!(
+        WotsSignature::from_bytes(&[0; 10]),
+        Err(Error::InvalidDecodedLength { expected: 2144, actual: 10 })
+    ));
+    let mut bytes = wots.to_bytes();
+    bytes[0] = 9;
+    assert!(WotsSigningKey::from_bytes(&bytes).is_err());
+    assert!(!format!("{:?}", wots).contains("secret_seed"));
+}
+
+#[test]
+fn tampered_signatures() {
+    let mut key = WotsSigningKey::from_seed(HashFamily::Sha256, [3; N]);
+    let public_key = key.public_key();
+    let mut bytes = key.sign(MESSAGE).unwrap().to_bytes();
+    bytes[5 * N] ^= 1;
+    let tampered = WotsSignature::from_bytes(&bytes).unwrap();
+    assert!(public_key.verify(MESSAGE, &tampered).is_err());
+}
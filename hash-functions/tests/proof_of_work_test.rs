@@ -0,0 +1,132 @@
+use hash_functions::algorithms::hasher::digest;
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::protocols::proof_of_work::{MemorySpentStore, SpentStore, Stamp, StampPolicy};
+use hash_functions::utils::error::Error;
+
+const RESOURCE: &str = "alice@example.org";
+// 2023-11-14 22:13:20 UTC
+const NOW: u64 = 1_700_000_000;
+const BITS: u32 = 12;
+
+fn mint(algorithm: Algorithm) -> Stamp {
+    Stamp::mint_at(algorithm, RESOURCE, BITS, NOW).unwrap()
+}
+
+#[test]
+fn mint_and_verify() {
+    for algorithm in [Algorithm::Sha256, Algorithm::Sha3_256, Algorithm::Sha512] {
+        let stamp = mint(algorithm);
+        let text = stamp.to_string();
+
+        assert!(text.starts_with("1:12:231114221320:alice@example.org::"));
+        assert!(stamp.value(algorithm) >= BITS);
+        let digest = digest(algorithm, text.as_bytes());
+        assert_eq!(digest.as_bytes()[0], 0);
+        assert_eq!(digest.as_bytes()[1] >> 4, 0);
+
+        let policy = StampPolicy::new(algorithm, BITS);
+        let verified = policy.verify_at(&text, RESOURCE, &mut MemorySpentStore::new(), NOW + 60).unwrap();
+        assert_eq!(verified, stamp);
+    }
+
+    let stamp = Stamp::mint(Algorithm::Sha256, RESOURCE, 8).unwrap();
+    let policy = StampPolicy::new(Algorithm::Sha256, 8);
+    assert!(policy.verify(&stamp.to_string(), RESOURCE, &mut MemorySpentStore::new()).is_ok());
+}
+
+#[test]
+fn replayed_stamps_are_rejected() {
+    let text = mint(Algorithm::Sha256).to_string();
+    let policy = StampPolicy::new(Algorithm::Sha256, BITS);
+    let mut spent = MemorySpentStore::new();
+
+    assert!(policy.verify_at(&text, RESOURCE, &mut spent, NOW).is_ok());
+    assert!(matches!(
+        policy.verify_at(&text, RESOURCE, &mut spent, NOW),
+        Err(Error::StampAlreadySpent)
+    ));
+    assert_eq!(spent.len(), 1);
+
+    // Once expired, the stamp can be forgotten: the policy refuses it by its date
+    spent.purge_before(NOW + 1);
+    assert!(spent.is_empty());
+    let later = NOW + policy.max_age + 1;
+    assert!(matches!(policy.verify_at(&text, RESOURCE, &mut spent, later), Err(Error::InvalidStamp(_))));
+}
+
+#[test]
+fn invalid_stamps_are_rejected() {
+    let stamp = mint(Algorithm::Sha256);
+    let policy = StampPolicy::new(Algorithm::Sha256, BITS);
+    let mut spent = MemorySpentStore::new();
+    let mut check = |text: &str, resource: &str, now: u64| policy.verify_at(text, resource, &mut spent, now);
+
+    assert!(check(&stamp.to_string(), "mallory@example.org", NOW).is_err());
+    assert!(check(&stamp.to_string(), RESOURCE, NOW - 2 * 3600).is_err());
+
+    // The claimed bits are above the actual work: a different counter
+    let mut forged = stamp.clone();
+    forged.counter = (0..)
+        .map(|attempt| format!("forged{}", attempt))
+        .find(|counter| Stamp { counter: counter.clone(), ..stamp.clone() }.value(Algorithm::Sha256) < BITS)
+        .unwrap();
+    assert!(check(&forged.to_string(), RESOURCE, NOW).is_err());
+
+    // Below the policy
+    let cheap = Stamp::mint_at(Algorithm::Sha256, RESOURCE, 4, NOW).unwrap();
+    assert!(check(&cheap.to_string(), RESOURCE, NOW).is_err());
+
+    for text in [
+        "",
+        "1:12:231114221320:alice@example.org::AAAA",
+        "0:12:231114221320:alice@example.org::AAAA:A",
+        "1:012:231114221320:alice@example.org::AAAA:A",
+        "1:12:231314221320:alice@example.org::AAAA:A",
+        "1:12:2311142213:alice@example.org::AAAA:A:extra",
+    ] {
+        assert!(matches!(check(text, RESOURCE, NOW), Err(Error::InvalidStamp(_))), "{}", text);
+    }
+    // Invalid stamps never reach the store
+    assert!(spent.is_empty());
+}
+
+#[test]
+fn dates() {
+    let stamp: Stamp = "1:20:040806:foo::7cd5ae0b3ab4b5c1:5ae7a".parse().unwrap();
+    assert_eq!(stamp.timestamp().unwrap(), 1_091_750_400);
+    assert_eq!(stamp.to_string(), "1:20:040806:foo::7cd5ae0b3ab4b5c1:5ae7a");
+
+    let minutes: Stamp = "1:20:2402291159:foo::r:c".parse().unwrap();
+    assert_eq!(minutes.timestamp().unwrap(), 1_709_207_940);
+    assert!("1:20:230229:foo::r:c".parse::<Stamp>().is_err());
+
+    assert_eq!(mint(Algorithm::Sha256).timestamp().unwrap(), NOW);
+    assert!(Stamp::mint_at(Algorithm::Sha256, "a:b", 1, NOW).is_err());
+    assert!(matches!(
+        Stamp::mint_at(Algorithm::Sha256, RESOURCE, 64, NOW),
+        Err(Error::InvalidParameter(_))
+    ));
+}
+
+// A store shared between servers, here a vector standing for a database table
+#[test]
+fn custom_spent_store() {
+    struct Table(Vec<String>);
+
+    impl SpentStore for Table {
+        fn spend(&mut self, stamp: &str, _timestamp: u64) -> Result<bool, Error> {
+            if self.0.iter().any(|spent| spent == stamp) {
+                return Ok(false);
+            }
+            self.0.push(stamp.to_string());
+            Ok(true)
+        }
+    }
+
+    let text = mint(Algorithm::Sha256).to_string();
+    let policy = StampPolicy::new(Algorithm::Sha256, BITS);
+    let mut table = Table(Vec::new());
+    assert!(policy.verify_at(&text, RESOURCE, &mut table, NOW).is_ok());
+    assert!(policy.verify_at(&text, RESOURCE, &mut table, NOW).is_err());
+    assert_eq!(table.0, [text]);
+}
@@ -0,0 +1,101 @@
+use std::fs;
+use std::io;
+
+use hash_functions::algorithms::hasher::digest;
+use hash_functions::algorithms::resumable_hasher::ResumableHasher;
+use hash_functions::elements::algorithm::Algorithm;
+use hash_functions::utils::error::Error;
+use tempfile::TempDir;
+
+fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 31 % 251) as u8).collect()
+}
+
+#[test]
+fn same_digest_as_hasher() {
+    // Lengths around the SHA-2 blocks and the sponge rates (72 to 168 bytes)
+    for algorithm in Algorithm::ALL {
+        for len in [0, 1, 55, 56, 63, 64, 71, 72, 111, 112, 128, 135, 136, 144, 168, 1000] {
+            let data = data(len);
+            let mut hasher = ResumableHasher::new(algorithm);
+            hasher.update(&data).unwrap();
+            assert_eq!(hasher.finalize(), digest(algorithm, &data), "{} of {} bytes", algorithm, len);
+        }
+    }
+}
+
+#[test]
+fn resume_from_saved_state() {
+    let data = data(5000);
+    for algorithm in Algorithm::ALL {
+        let expected = digest(algorithm, &data);
+        for split in [0, 1, 64, 100, 2500, 4999, 5000] {
+            let mut hasher = ResumableHasher::new(algorithm);
+            hasher.update(&data[..split]).unwrap();
+            let saved = hasher.to_bytes();
+            drop(hasher);
+
+            let mut resumed = ResumableHasher::from_bytes(&saved).unwrap();
+            assert_eq!(resumed.processed_len(), split as u64);
+            resumed.update(&data[split..]).unwrap();
+            assert_eq!(resumed.finalize(), expected, "{} resumed at {}", algorithm, split);
+        }
+    }
+}
+
+#[test]
+fn upload_across_restarts() {
+    let dir = TempDir::new().unwrap();
+    let state_path = dir.path().join("upload.state");
+    let upload = data(100_000);
+
+    // Each piece is hashed by a fresh process that reloads the state left by the previous one
+    for piece in upload.chunks(7_000) {
+        let mut hasher = match fs::read(&state_path) {
+            Ok(bytes) => ResumableHasher::from_bytes(&bytes).unwrap(),
+            Err(_) => ResumableHasher::new(Algorithm::Sha512),
+        };
+        io::copy(&mut &piece[..], &mut hasher).unwrap();
+        fs::write(&state_path, hasher.to_bytes()).unwrap();
+    }
+
+    let hasher = ResumableHasher::from_bytes(&fs::read(&state_path).unwrap()).unwrap();
+    assert_eq!(hasher.processed_len(), 100_000);
+    assert_eq!(hasher.finalize(), digest(Algorithm::Sha512, &upload));
+}
+
+#[test]
+fn invalid_states() {
+    let mut hasher = ResumableHasher::new(Algorithm::Sha256);
+    hasher.update(b"abc").unwrap();
+    let bytes = hasher.to_bytes();
+    assert_eq!(bytes.len(), 16 + 32 + 3);
+    assert_eq!(ResumableHasher::from_bytes(&bytes).unwrap(), hasher);
+
+    assert!(matches!(
+        ResumableHasher::from_bytes(&bytes[..50]),
+        Err(Error::InvalidDecodedLength { expected: 51, actual: 50 })
+    ));
+    assert!(matches!(ResumableHasher::from_bytes(&bytes[..10]), Err(Error::InvalidDecodedLength { .. })));
+
+    // SHAKE128 has no fixed output, there is nothing to resume
+    let mut shake = bytes.clone();
+    shake[7] = 0x18;
+    assert!(matches!(ResumableHasher::from_bytes(&shake), Err(Error::UnsupportedAlgorithm(_))));
+    let mut unknown = bytes.clone();
+    unknown[7] = 0xff;
+    assert!(matches!(ResumableHasher::from_bytes(&unknown), Err(Error::UnknownMultihashCode(_))));
+
+    // The length in bits must fit the padding
+    let mut too_long = bytes[..48].to_vec();
+    too_long[8..16].copy_from_slice(&(1u64 << 61).to_be_bytes());
+    assert!(matches!(ResumableHasher::from_bytes(&too_long), Err(Error::InvalidParameter(_))));
+
+    let mut longest = bytes[..48].to_vec();
+    longest[8..16].copy_from_slice(&((1u64 << 61) - 1).to_be_bytes());
+    longest.extend_from_slice(&[0; 63]);
+    let mut hasher = ResumableHasher::from_bytes(&longest).unwrap();
+    assert!(matches!(hasher.update(b"x"), Err(Error::InvalidParameter(_))));
+    assert!(hasher.update(b"").is_ok());
+    assert_eq!(hasher.finalize().as_bytes().len(), 32);
+}
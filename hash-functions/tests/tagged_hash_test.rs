@@ -0,0 +1,65 @@
+use std::io;
+
+use hash_functions::algorithms::hasher::digest;
+use hash_functions::algorithms::tagged_hash::{tagged_hash, TaggedHash};
+use hash_functions::algorithms::xof_algo::cshake;
+use hash_functions::elements::algorithm::{Algorithm, XofAlgorithm};
+
+// SHA256(SHA256(tag) || SHA256(tag) || message), computed with Python's hashlib
+const BIP0340_CHALLENGE_EMPTY: &str = "c216d352f5818b7b4beacd4ae0a26fe888080823d2a598856661bcd54f1b3713";
+const BIP0340_AUX_ZEROS: &str = "54f169cfc9e2e5727480441f90ba25c488f461c70b5ea5dcaaf7af69270aa514";
+
+// SP 800-185 cSHAKE128 sample #1: data 00 01 02 03, S = "Email Signature"
+const CSHAKE128_SAMPLE_1: &str = "c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5";
+
+#[test]
+fn sha256_tagged_hash() {
+    assert_eq!(hex::encode(tagged_hash(b"BIP0340/challenge", b"")), BIP0340_CHALLENGE_EMPTY);
+    assert_eq!(hex::encode(tagged_hash(b"BIP0340/aux", &[0; 32])), BIP0340_AUX_ZEROS);
+
+    let tagged = TaggedHash::sha256(b"BIP0340/aux");
+    assert_eq!(tagged.tag(), b"BIP0340/aux");
+    assert_eq!(tagged.output_len(), 32);
+    assert_eq!(hex::encode(tagged.hash(&[0; 32])), BIP0340_AUX_ZEROS);
+
+    // Same as spelling out the prefix
+    let tag_hash = digest(Algorithm::Sha256, b"BIP0340/aux").into_bytes();
+    let message = [&tag_hash[..], &tag_hash, b"message"].concat();
+    assert_eq!(tagged.hash(b"message"), digest(Algorithm::Sha256, &message).into_bytes());
+}
+
+#[test]
+fn cshake_tagged_hash() {
+    let tagged = TaggedHash::cshake(XofAlgorithm::Shake128, b"Email Signature");
+    assert_eq!(hex::encode(tagged.hash(&[0, 1, 2, 3])), CSHAKE128_SAMPLE_1);
+
+    let tagged = TaggedHash::cshake(XofAlgorithm::Shake256, b"credentials");
+    assert_eq!(tagged.output_len(), 64);
+    assert_eq!(tagged.hash(b"alice"), cshake(XofAlgorithm::Shake256, b"credentials", b"alice", 64));
+}
+
+#[test]
+fn tags_separate_domains() {
+    for (username, credentials) in [
+        (TaggedHash::sha256(b"identity/username"), TaggedHash::sha256(b"identity/credentials")),
+        (
+            TaggedHash::cshake(XofAlgorithm::Shake128, b"identity/username"),
+            TaggedHash::cshake(XofAlgorithm::Shake128, b"identity/credentials"),
+        ),
+    ] {
+        assert_ne!(username.hash(b"alice"), credentials.hash(b"alice"));
+        assert_eq!(username.hash(b"alice"), username.clone().hash(b"alice"));
+    }
+    assert_ne!(tagged_hash(b"identity/username", b"alice").to_vec(), digest(Algorithm::Sha256, b"alice").into_bytes());
+}
+
+#[test]
+fn incremental_hashing() {
+    for tagged in [TaggedHash::sha256(b"chunks"), TaggedHash::cshake(XofAlgorithm::Shake256, b"chunks")] {
+        let data = vec![7u8; 1000];
+        let mut hasher = tagged.hasher();
+        hasher.update(&data[..10]);
+        io::copy(&mut &data[10..], &mut hasher).unwrap();
+        assert_eq!(hasher.finalize(), tagged.hash(&data));
+    }
+}
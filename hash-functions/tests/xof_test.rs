@@ -0,0 +1,166 @@
+use std::process::Command;
+
+use hash_functions::algorithms::kmac_algo::{kmac, Kmac};
+use hash_functions::algorithms::xof_algo::{cshake, shake, Xof};
+use hash_functions::elements::algorithm::XofAlgorithm;
+use hash_functions::utils::error::Error;
+
+const BINARY: &str = env!("CARGO_BIN_EXE_hash-functions");
+const SHAKE128_EMPTY: &str = "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26";
+const SHAKE256_EMPTY: &str = "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762fd75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be";
+
+// SP 800-185 cSHAKE samples #1 and #3: data 00 01 02 03, S = "Email Signature"
+const EMAIL_SIGNATURE: &[u8; 15] = b"Email Signature";
+const CSHAKE128_SAMPLE_1: &str = "c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5";
+const CSHAKE256_SAMPLE_3: &str = "d008828e2b80ac9d2218ffee1d070c48b8e4c87bff32c9699d5b6896eee0edd164020e2be0560858d9c00c037e34a96937c561a74c412bb4c746469527281c8c";
+
+// SP 800-185 KMAC samples, key 40 41 .. 5f
+const TAGGED_APPLICATION: &[u8; 21] = b"My Tagged Application";
+const KMAC128_SAMPLE_1: &str = "e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e";
+const KMAC128_SAMPLE_2: &str = "3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5";
+const KMAC128_SAMPLE_3: &str = "1f5b4e6cca02209e0dcb5ca635b89a15e271ecc760071dfd805faa38f9729230";
+const KMAC256_SAMPLE_4: &str = "20c570c31346f703c9ac36c61c03cb64c3970d0cfc787e9b79599d273a68d2f7f69d4cc3de9d104a351689f27cf6f5951f0103f33f4f24871024d9c27773a8dd";
+const KMAC256_SAMPLE_5: &str = "75358cf39e41494e949707927cee0af20a3ff553904c86b08f21cc414bcfd691589d27cf5e15369cbbff8b9a4c2eb17800855d0235ff635da82533ec6b759b69";
+const KMACXOF128_SAMPLE_1: &str = "cd83740bbd92ccc8cf032b1481a0f4460e7ca9dd12b08a0c4031178bacd6ec35";
+
+fn kmac_key() -> Vec<u8> {
+    (0x40..=0x5f).collect()
+}
+
+#[test]
+fn shake_of_empty_message() {
+    assert_eq!(hex::encode(shake(XofAlgorithm::Shake128, b"", 32)), SHAKE128_EMPTY);
+    assert_eq!(hex::encode(shake(XofAlgorithm::Shake256, b"", 64)), SHAKE256_EMPTY);
+}
+
+#[test]
+fn output_is_a_stream() {
+    let long = shake(XofAlgorithm::Shake128, b"abc", 500);
+    assert_eq!(shake(XofAlgorithm::Shake128, b"abc", 17), &long[..17]);
+
+    let mut xof = Xof::shake(XofAlgorithm::Shake128);
+    xof.update(b"a");
+    xof.update(b"bc");
+    let mut reader = xof.finalize_xof();
+    let (mut first, mut second) = ([0u8; 200], [0u8; 300]);
+    reader.read(&mut first);
+    reader.read(&mut second);
+
+    assert_eq!([first.as_slice(), second.as_slice()].concat(), long);
+}
+
+#[test]
+fn cshake_samples() {
+    let data = [0x00, 0x01, 0x02, 0x03];
+
+    assert_eq!(
+        hex::encode(cshake(XofAlgorithm::Shake128, EMAIL_SIGNATURE, &data, 32)),
+        CSHAKE128_SAMPLE_1
+    );
+    assert_eq!(
+        hex::encode(cshake(XofAlgorithm::Shake256, EMAIL_SIGNATURE, &data, 64)),
+        CSHAKE256_SAMPLE_3
+    );
+    // Without customization cSHAKE falls back to SHAKE
+    assert_eq!(
+        cshake(XofAlgorithm::Shake256, b"", &data, 64),
+        shake(XofAlgorithm::Shake256, &data, 64)
+    );
+}
+
+#[test]
+fn kmac_samples() {
+    let key = kmac_key();
+    let short = [0x00, 0x01, 0x02, 0x03];
+    let long: Vec<u8> = (0x00..=0xc7).collect();
+
+    assert_eq!(hex::encode(kmac(XofAlgorithm::Shake128, &key, b"", &short, 32)), KMAC128_SAMPLE_1);
+    assert_eq!(hex::encode(kmac(XofAlgorithm::Shake128, &key, TAGGED_APPLICATION, &short, 32)), KMAC128_SAMPLE_2);
+    assert_eq!(hex::encode(kmac(XofAlgorithm::Shake128, &key, TAGGED_APPLICATION, &long, 32)), KMAC128_SAMPLE_3);
+    assert_eq!(hex::encode(kmac(XofAlgorithm::Shake256, &key, TAGGED_APPLICATION, &short, 64)), KMAC256_SAMPLE_4);
+    assert_eq!(hex::encode(kmac(XofAlgorithm::Shake256, &key, b"", &long, 64)), KMAC256_SAMPLE_5);
+
+    let mut xof = Kmac::new(XofAlgorithm::Shake128, &key, b"");
+    xof.update(&short);
+    assert_eq!(hex::encode(xof.finalize_xof(32)), KMACXOF128_SAMPLE_1);
+}
+
+#[test]
+fn kmac_verification_and_length_binding() {
+    let key = kmac_key();
+    let tag = kmac(XofAlgorithm::Shake256, &key, TAGGED_APPLICATION, b"message", 32);
+
+    let mut mac = Kmac::new(XofAlgorithm::Shake256, &key, TAGGED_APPLICATION);
+    mac.update(b"message");
+    assert!(mac.clone().verify(&tag).is_ok());
+    // The length is an input, so the truncated tag is not valid as a shorter tag
+    assert!(mac.clone().verify(&tag[..16]).is_err());
+    assert_ne!(mac.finalize(64)[..32], tag[..]);
+
+    let mut other_customization = Kmac::new(XofAlgorithm::Shake256, &key, b"Other Application");
+    other_customization.update(b"message");
+    assert!(other_customization.verify(&tag).is_err());
+
+    // Short tags fail even when they are the right output
+    for length in [0, 1, 15] {
+        let short = kmac(XofAlgorithm::Shake256, &key, TAGGED_APPLICATION, b"message", length);
+        let mut mac = Kmac::new(XofAlgorithm::Shake256, &key, TAGGED_APPLICATION);
+        mac.update(b"message");
+        assert!(matches!(mac.verify(&short), Err(Error::InvalidMac)));
+    }
+}
+
+#[test]
+fn shake_from_binary() {
+    let output = Command::new(BINARY)
+        .args(["--algorithm", "shake128", "--length", "256", "--tag", "/dev/null"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("SHAKE128 (/dev/null) = {}\n", SHAKE128_EMPTY)
+    );
+
+    let wrong_length = Command::new(BINARY)
+        .args(["--algorithm", "sha256", "--length", "256", "/dev/null"])
+        .output()
+        .unwrap();
+    assert!(!wrong_length.status.success());
+}
+
+#[test]
+fn kmac_and_cshake_from_binary() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("sample");
+    std::fs::write(&path, [0, 1, 2, 3]).unwrap();
+    let path = path.to_str().unwrap();
+    let key = hex::encode(kmac_key());
+
+    let run = |args: &[&str]| {
+        let output = Command::new(BINARY).args(args).output().unwrap();
+        assert!(output.status.success(), "{:?}", args);
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert_eq!(
+        run(&["kmac", "--key", &key, "--algorithm", "shake128", "--customization", "My Tagged Application", path]),
+        format!("{}  {}\n", KMAC128_SAMPLE_2, path)
+    );
+    assert_eq!(
+        run(&["kmac", "--key", &key, "--algorithm", "shake128", "--xof", "--length", "256", path]),
+        format!("{}  {}\n", KMACXOF128_SAMPLE_1, path)
+    );
+    assert_eq!(
+        run(&["cshake", "--algorithm", "shake128", "--customization", "Email Signature", path]),
+        format!("{}  {}\n", CSHAKE128_SAMPLE_1, path)
+    );
+    assert_eq!(
+        run(&["cshake", "--customization", "Email Signature", "--length", "512", path]),
+        format!("{}  {}\n", CSHAKE256_SAMPLE_3, path)
+    );
+
+    for args in [["kmac", "--key", "zz", path], ["cshake", "--length", "12", path]] {
+        assert!(!Command::new(BINARY).args(args).output().unwrap().status.success());
+    }
+}
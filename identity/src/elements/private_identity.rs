@@ -3,9 +3,14 @@ use anyhow::anyhow;
 use ed25519_dalek::{ed25519::signature::SignerMut, Signature, SigningKey, Verifier};
 use ed25519_dalek::pkcs8::{ EncodePrivateKey, DecodePrivateKey };
 use ed25519_dalek::pkcs8::spki::der::pem::LineEnding;
+use hash_functions::algorithms::hkdf_algo::Hkdf;
+use hash_functions::elements::{algorithm::Algorithm, encoding::Encoding};
 use crate::utils::{error::Error, hasher::hash_input};
 use super::version::Version;
 
+// HKDF label of the ed25519 identity key
+const IDENTITY_KEY_INFO: &[u8] = b"identity/ed25519";
+
 // An identity that includes public and private key components.
 // This can be used to both sign and verify.
 pub struct PrivateIdentity {
@@ -22,34 +27,43 @@ pub struct PrivateIdentity {
 }
 
 impl PrivateIdentity {
+	// Version 1 derivation, the key existing credentials always produced
     pub fn new(username: &String, password: &String) -> anyhow::Result<PrivateIdentity> {
+		Self::new_with_version(username, password, Version::V1)
+	}
+
+	// Derives the keys of the credentials under the given version of the derivation algorithm
+    pub fn new_with_version(username: &String, password: &String, version: Version) -> anyhow::Result<PrivateIdentity> {
 
         let username_hash = hash_input(username).map_err(|e| anyhow!(e))?;
 		println!("Username hash: {:}", username_hash.len());
 
-        let public_id = base64_url::encode(&username_hash);
+        let public_id = Encoding::Base64Url.encode(username_hash.as_bytes());
 		println!("public id hash base64_URL: {:}", public_id);
 
         let credentials = format!("{}{}", username, password);
         let hashed_credentials = hash_input(&credentials)
 			.map_err(|e| anyhow!(e))?;
 
-		// TODO: The length of the byte array now is 32 bytes. We have the default value of the hash
-		// which is 32 bytes
-        let secret_32: [u8; 32] = hashed_credentials.as_bytes()[..32]
-            .try_into()
-            .map_err(|e: TryFromSliceError| anyhow!(e))?;
-
-        // Other way to split an array
-        // let mut example: [u8; 32] = [0; 32];
-        // example.copy_from_slice(&hashed_credentials.as_bytes()[..32]);
+        let secret_32: [u8; 32] = match version {
+			// The credentials hash itself is the signing key
+			Version::V1 => hashed_credentials.as_bytes()[..32]
+				.try_into()
+				.map_err(|e: TryFromSliceError| anyhow!(e))?,
+			// The signing key is derived from the credentials hash, not the hash itself, so the same
+			// hash can feed other keys under other labels
+			Version::V2 => Hkdf::extract(Algorithm::Sha256, None, hashed_credentials.as_bytes())
+				.expand_array(IDENTITY_KEY_INFO)
+				.map_err(|e| anyhow!(e))?,
+			Version::Unknown => return Err(anyhow!(Error::InvalidUnknownVersion)),
+		};
 
         let keypair = SigningKey::from_bytes(&secret_32);
 
         let identity = PrivateIdentity {
 			public_id,
 			keypair,
-			version: Version::V1
+			version
 		};
 
 		Ok(identity)
@@ -75,10 +89,8 @@ impl PrivateIdentity {
 	}
 
 	pub fn to_base64_url(&self) -> anyhow::Result<String, Error> {
-		let public_id_binary = match base64_url::decode(&self.public_id) {
-			Ok(binary) => binary,
-			Err(_) => return Err(Error::InvalidBase64String)
-		};
+		let public_id_binary = Encoding::Base64Url.decode(&self.public_id)
+			.map_err(|_| Error::InvalidBase64String)?;
 
 		let binary_vector: Vec<u8> = [
 			&[self.version.to_u8()], // 1 byte
@@ -87,23 +99,22 @@ impl PrivateIdentity {
 			&self.keypair.to_keypair_bytes() // 64 byte
 		].concat();
 
-		Ok(base64_url::encode(&binary_vector))
+		Ok(Encoding::Base64Url.encode(&binary_vector))
 	}
 
 	pub fn from_base64_url(private_identity: String) -> anyhow::Result<PrivateIdentity, Error> {
-		let binary = match base64_url::decode(&private_identity) {
-			Ok(private) => private,
-			Err(_)	=> return Err(Error::InvalidBase64String)
-		};
+		let binary = Encoding::Base64Url.decode(&private_identity)
+			.map_err(|_| Error::InvalidBase64String)?;
 
 		if binary.is_empty() { return Err(Error::EmptyString) }
 
 		let version = Version::from_u8(&binary[0]);
 
 		match version {
-			Version::V1 => {
+			// Both versions store the same keys, they only differ in how `new` derives them
+			Version::V1 | Version::V2 => {
 				if binary.len() != 97 { return Err(Error::InvalidPrivateIdentity)}
-				let public_id = base64_url::encode(&binary[1..33]);
+				let public_id = Encoding::Base64Url.encode(&binary[1..33]);
 
 				let binary_keypair: &[u8; 64] = &binary[33..97]
 					.try_into()
@@ -118,7 +129,7 @@ impl PrivateIdentity {
 					keypair
 				})
 			},
-			Version::Unknown => return Err(Error::InvalidUnknownVersion)
+			Version::Unknown => Err(Error::InvalidUnknownVersion)
 		}
 	}
 
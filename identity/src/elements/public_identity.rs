@@ -1,6 +1,8 @@
 use anyhow::anyhow;
 use ed25519_dalek::VerifyingKey;
 
+use hash_functions::elements::encoding::Encoding;
+
 use crate::utils::error::Error;
 
 use super::{private_identity::PrivateIdentity, version::Version};
@@ -24,7 +26,11 @@ pub struct PublicIdentity {
 impl PublicIdentity {
 
 	pub fn new(username: &String, password: &String) -> anyhow::Result<Self> {
-		let private_identity = PrivateIdentity::new(username, password)?;
+		Self::new_with_version(username, password, Version::V1)
+	}
+
+	pub fn new_with_version(username: &String, password: &String, version: Version) -> anyhow::Result<Self> {
+		let private_identity = PrivateIdentity::new_with_version(username, password, version)?;
 		Ok(Self {
 			public_id: private_identity.public_id,
 			public_key: private_identity.keypair.verifying_key(),
@@ -36,10 +42,8 @@ impl PublicIdentity {
 	pub fn to_base64_url(self) -> anyhow::Result<String> {
 		// Decode the base64url to binary array. The size is going to be decresed to 32 bytes
 		// if the default length is 32 bytes
-		let public_id_binary = match base64_url::decode(&self.public_id) {
-			Ok(res) => res,
-			Err(e) => return Err(anyhow!(e))
-		};
+		let public_id_binary = Encoding::Base64Url.decode(&self.public_id)
+			.map_err(|e| anyhow!(e))?;
 
 		let merged_properties: Vec<u8> = [
 			&[self.version.to_u8()], // 1 byte
@@ -48,14 +52,12 @@ impl PublicIdentity {
 			self.public_key.as_bytes() // 32 bytes
 		].concat();
 
-		Ok(base64_url::encode(&merged_properties))
+		Ok(Encoding::Base64Url.encode(&merged_properties))
 	}
 
 	pub fn from_base64_url(identity: String) -> anyhow::Result<PublicIdentity, Error> {
-		let identity_binary = match base64_url::decode(&identity) {
-			Ok(data) => data,
-			Err(_) => return Err(Error::InvalidBase64String)
-		};
+		let identity_binary = Encoding::Base64Url.decode(&identity)
+			.map_err(|_| Error::InvalidBase64String)?;
 
 		if identity_binary.is_empty() { return Err(Error::EmptyString); }
 
@@ -63,9 +65,10 @@ impl PublicIdentity {
 		let version = Version::from_u8(&version_u8);
 
 		match version {
-			Version::V1 => {
+			// Same layout in both versions, only the key derivation changed
+			Version::V1 | Version::V2 => {
 				if identity_binary.len() != 65 { return Err(Error::InvalidPublicIdentity)}
-				let public_id = base64_url::encode(&identity_binary[1..33] );
+				let public_id = Encoding::Base64Url.encode(&identity_binary[1..33] );
 
 				// Create a 32 byte array to create the verifying key
 				let mut public_key_binary: [u8; 32] = [0; 32];
@@ -75,13 +78,13 @@ impl PublicIdentity {
 					Err(_) => return Err(Error::InvalidPublicKey)
 				};
 				// Create the public identity
-				return Ok(PublicIdentity {
+				Ok(PublicIdentity {
 					public_id,
 					public_key,
 					version
-				});
+				})
 			},
-			Version::Unknown => return Err(Error::InvalidUnknownVersion)
+			Version::Unknown => Err(Error::InvalidUnknownVersion)
 		}
 	}
 }
\ No newline at end of file
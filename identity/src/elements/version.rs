@@ -5,6 +5,10 @@ pub enum Version {
 	/// EdDSA + Argon2di.
 	V1 = 1,
 
+	// The signing key is derived from the Argon2 output with HKDF instead of being the output itself.
+	/// EdDSA + Argon2di + HKDF-SHA256.
+	V2 = 2,
+
 	// Catch-all version.
 	Unknown,
 }
@@ -14,6 +18,7 @@ impl Version {
 	pub fn to_u8(&self) -> u8 {
 		match self {
 			Version::V1 => 1u8,
+			Version::V2 => 2u8,
 			Version::Unknown => 0u8,
 		}
 	}
@@ -22,6 +27,7 @@ impl Version {
 	pub fn from_u8(version: &u8) -> Version {
 		match version {
 			1 => Version::V1,
+			2 => Version::V2,
 			_ => Version::Unknown,
 		}
 	}
@@ -3,7 +3,7 @@ use argon2::{password_hash::{Output, SaltString}, Argon2, PasswordHash, Password
 use sha2::{Digest, Sha256, Sha512};
 
 use crate::elements::argon_algorithm::{params_v1, ARGON2_SALT, ARGON_ALGORITHM, ARGON_VERSION};
-use base64ct::{Base64, Base64Unpadded, Encoding};
+use hash_functions::elements::encoding::Encoding;
 
 // We will use sha256 algorithm because if we use sha512 algorithm, the hash output
 // is 64 bytes. After, when we parse to SaltString, it overpasses the Salt::MAX_LENGTH
@@ -23,7 +23,7 @@ pub fn sha256_hash_to_hex(input: &str) -> String {
     //Base64::encode_string(&input_hash)
     // The problem of that encoding is that it doubles the size of the bytes
     // In our case till 64 bytes
-    hex::encode(&input_hash)
+    Encoding::Hex.encode(&input_hash)
 }
 
 pub fn sha512_hash_to_base64(input: &str) -> String {
@@ -31,11 +31,11 @@ pub fn sha512_hash_to_base64(input: &str) -> String {
     hasher.update(input);
     // sha512 creates a digest of 64 bytes. But it is an array bytes types, not string
     let hash_input = hasher.finalize();
-    let base64_input = Base64::encode_string(&hash_input);
-    // Create a chunk of 64 length because when we encode to base64, the length increase
+    // Keep 64 characters because when we encode to base64, the length increase
     // The reason of that below. Find "BACKGROUND" keyword
-    let (chunk, _) = base64_input.split_at(64);
-    chunk.to_string()
+    // Every 3 bytes are 4 characters, so the first 48 bytes give exactly those 64 characters,
+    // without padding. It is the same salt we got when the whole digest was encoded and cut
+    Encoding::Base64.encode(&hash_input[..48])
 }
 
 // Hash twice the input: Get the digest of sha512 and hash the password with argon2 function
@@ -111,15 +111,10 @@ fn _extract_binary_data_from_hash(input_hash: &PasswordHash, print_output: bool)
     // of 4 characters - or make sure that your base64 decoder can handle unpadded base64
 
     // TODO: That length, 32, should be base on the length of the hash
-    let mut slice_32: [u8; 32] = [0; 32];
-    let temporal_slice_for_bytes: &mut [u8] = &mut slice_32;
-
     // Get the original hash representation in binary
-    let byte_data = Base64Unpadded::decode(
-        input_hash.hash.unwrap().to_string(),
-        temporal_slice_for_bytes,
-    )
-    .unwrap();
+    let byte_data = Encoding::Base64Unpadded
+        .decode_exact(&input_hash.hash.unwrap().to_string(), 32)
+        .unwrap();
 
     if print_output {
         println!("hash BYTE data: {:?}", byte_data);
@@ -127,21 +122,19 @@ fn _extract_binary_data_from_hash(input_hash: &PasswordHash, print_output: bool)
     }
 
     // Get the base64 encoded string. It has to be the same as the input_hash
-    let mut slice_43: [u8; 43] = [0; 43];
-    let temporal_slice_for_str: &mut [u8] = &mut slice_43;
-    let back = Base64Unpadded::encode(byte_data, temporal_slice_for_str).unwrap();
+    let back = Encoding::Base64Unpadded.encode(&byte_data);
 
     if print_output {
         println!("argon base64 HASH: {:?}", back);
         println!("argon base64 hash LENGTH: {}", back.len());
     }
 
-    _to_base64_url(byte_data);
+    _to_base64_url(&byte_data);
 }
 
 fn _to_base64_url(slice: &[u8]) {
     println!("byte SLICE len(): {:}", slice.len());
-    let url = base64_url::encode(slice);
+    let url = Encoding::Base64Url.encode(slice);
     println!("URL encoded baseURL: {:}", url);
     println!("URL encoded baseURL len(): {:}", url.len());
 }
@@ -1,11 +1,15 @@
 use std::path::Path;
 
 use identity::elements::private_identity::PrivateIdentity;
+use identity::elements::version::Version;
 use identity::utils::environment::Environment;
 
 const BINARY_MSG: &[u8; 47] = b"walk a bit in the sand before shower in the sea";
 const MSG_SIGNATURE: &str = "B118C41734FAA0B1BB8CFF90DF12D1CF4E74592F1FE6B3A3A3FE5E5F219909DEF116172BE5A71BDDF9B8A90CD8063732206E2150572792E9478B1F484E031700";
 const BASE64_URL_IDENTITY: &str = "ASzMbRn6q8rLfJgFBOVx4ruuUG4arE6flhsO-_TKXG2B1bYr2kVKkKHwCO2Ly0-5mOwAb-O0RPi21EU0gYrKtAfnP0y9iS-U7WYwiKEFpHuFS_yMO2hgw1Bj36CEa6FDeg";
+// Same credentials under version 2, whose signing key is derived from the Argon2 output with HKDF
+const V2_MSG_SIGNATURE: &str = "2323E341729815A36C7F8FD3DA3C84A079F99AC721C5FC4645F50695F6570F57FECD3BAC8F4BE08DE537BA282565D611CD219FAC6DC119413D7D494B5D1C5503";
+const V2_BASE64_URL_IDENTITY: &str = "AizMbRn6q8rLfJgFBOVx4ruuUG4arE6flhsO-_TKXG2BDFjQYMevbIjKWGakTS4rF2Z3KqMU7dOgmwj4O11WSjI1CrVxqqqP5uiJYviW7dKEf1sgS-7bjedNmRrfGpOA2Q";
 const PEM_PATH: &str = "./files/key.pem";
 
 fn create_identity() -> PrivateIdentity {
@@ -17,6 +21,16 @@ fn create_identity() -> PrivateIdentity {
     PrivateIdentity::new(username, password).unwrap()
 }
 
+fn create_v2_identity() -> PrivateIdentity {
+    let env = Environment::init().unwrap();
+
+    let username = &env.username;
+    let password = &env.password;
+
+    PrivateIdentity::new_with_version(username, password, Version::V2).unwrap()
+}
+
+#[allow(clippy::single_match)]
 fn create_pem_file() {
     let identity = create_identity();
     match Path::new(PEM_PATH).exists() {
@@ -59,6 +73,45 @@ fn create_identity_from_base64_url() {
 }
 
 
+#[test]
+fn sign_message_from_v2_identity() {
+    let mut identity = create_v2_identity();
+    let signature = identity.sign(BINARY_MSG);
+
+    assert_eq!(
+        signature.to_string(), 
+        V2_MSG_SIGNATURE
+    );
+}
+
+#[test]
+fn parse_v2_to_base64_url() {
+    let identity = create_v2_identity();
+    
+    assert_eq!(
+        identity.to_base64_url().unwrap(),
+        V2_BASE64_URL_IDENTITY
+    )
+}
+
+#[test]
+fn create_v2_identity_from_base64_url() {
+    let mut identity = PrivateIdentity::from_base64_url(V2_BASE64_URL_IDENTITY.to_string()).unwrap();
+    let signature = identity.sign(BINARY_MSG);
+
+    assert_eq!(
+        signature.to_string(), 
+        V2_MSG_SIGNATURE
+    );
+}
+
+#[test]
+fn unknown_version_is_rejected() {
+    let env = Environment::init().unwrap();
+
+    assert!(PrivateIdentity::new_with_version(&env.username, &env.password, Version::Unknown).is_err());
+}
+
 #[test]
 fn sign_message_from_pem_file() {
     create_pem_file();
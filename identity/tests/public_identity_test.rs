@@ -1,6 +1,9 @@
 use identity::{elements::public_identity::PublicIdentity, utils::{environment::Environment, hasher::hash_input}};
+use identity::elements::version::Version;
 
 const BASE64_URL_IDENTITY: &str = "ASzMbRn6q8rLfJgFBOVx4ruuUG4arE6flhsO-_TKXG2B5z9MvYkvlO1mMIihBaR7hUv8jDtoYMNQY9-ghGuhQ3o";
+// Version 2 identity of the same credentials
+const V2_BASE64_URL_IDENTITY: &str = "AizMbRn6q8rLfJgFBOVx4ruuUG4arE6flhsO-_TKXG2BNQq1caqqj-boiWL4lu3ShH9bIEvu243nTZka3xqTgNk";
 const PUBLIC_ID: &str = "LMxtGfqryst8mAUE5XHiu65QbhqsTp-WGw779MpcbYE";
 const USERNAME: &str = "rustykey";
 
@@ -35,6 +38,23 @@ fn get_credentials_from_base64_url() {
     );
 }
 
+#[test]
+fn v2_public_identity() {
+    let env = Environment::init().unwrap();
+    let public_identity = PublicIdentity::new_with_version(&env.username, &env.password, Version::V2).unwrap();
+
+    assert_eq!(
+        public_identity.to_base64_url().unwrap(),
+        V2_BASE64_URL_IDENTITY
+    );
+
+    let public_itendity = PublicIdentity::from_base64_url(V2_BASE64_URL_IDENTITY.to_string()).unwrap();
+    assert_eq!(
+        PUBLIC_ID,
+        public_itendity.public_id
+    );
+}
+
 #[test]
 fn generate_public_id_from_username() {
     let username_hash = hash_input(&String::from(USERNAME)).unwrap();
@@ -5,6 +5,8 @@ use crate::templates;
 pub struct ArgonUtils {}
 
 impl ArgonUtils {
+    // Returns the PHC string instead of `Self`, the struct is only a namespace
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(config: templates::EncryptionData, password: &[u8]) -> anyhow::Result<String> {
         let ident = Ident::try_from(config.variant.as_str()).map_err(|e| anyhow!(e))?;
         let algorithm = Algorithm::try_from(ident).map_err(|e| anyhow!(e))?;